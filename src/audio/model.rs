@@ -3,10 +3,17 @@
 use super::*;
 use std::time::Instant;
 
+/// The number of note events which can be waiting for a later buffer. Any
+/// more are dropped, so the audio thread never allocates.
+pub const PENDING_EVENT_CAPACITY: usize = 256;
+
 pub struct AudioModel {
     pub voice_handler: VoiceHandler,
 
     pub note_receiver: mpsc::Receiver<NoteEvent>,
+    /// Received note events which haven't been reached yet, in time order.
+    /// Their timing is relative to the start of the next buffer.
+    pub pending_events: Vec<NoteEvent>,
     callback_timer: Instant,
     pub callback_delta_time: Arc<Atomic<f32>>,
 
@@ -18,6 +25,7 @@ impl AudioModel {
         Self {
             voice_handler: VoiceHandler::build(Arc::clone(&ctx.sample_rate)),
             note_receiver: ctx.note_receiver,
+            pending_events: Vec::with_capacity(PENDING_EVENT_CAPACITY),
             callback_timer: Instant::now(),
             callback_delta_time: Arc::new(Atomic::new(0.0)),
             sample_rate: ctx.sample_rate,
@@ -1,6 +1,9 @@
 //! Module for the audio processing callback.
 
-use super::{model::AudioModel, voice::*};
+use super::{
+    model::{AudioModel, PENDING_EVENT_CAPACITY},
+    voice::*,
+};
 use crate::prelude::*;
 
 pub const MAX_BLOCK_SIZE: usize = 64;
@@ -8,9 +11,18 @@ pub const MAX_BLOCK_SIZE: usize = 64;
 pub fn process(audio: &mut AudioModel, buffer: &mut Buffer) {
     let buffer_len = buffer.len_frames();
 
-    let mut next_event = audio.note_receiver.try_recv().ok();
+    // events may be scheduled beyond this buffer (such as when they are
+    // delayed by humanisation), so they are queued until they are reached
+    while let Ok(event) = audio.note_receiver.try_recv() {
+        if audio.pending_events.len() < PENDING_EVENT_CAPACITY {
+            audio.pending_events.push(event);
+        }
+    }
+    audio.pending_events.sort_unstable_by_key(NoteEvent::timing);
 
     let voice_handler = &mut audio.voice_handler;
+    let pending_events = &audio.pending_events;
+    let mut event_idx = 0;
 
     let mut block_start: usize = 0;
     let mut block_end = MAX_BLOCK_SIZE.min(buffer_len);
@@ -19,22 +31,22 @@ pub fn process(audio: &mut AudioModel, buffer: &mut Buffer) {
     while block_start < buffer_len {
         // first, handle incoming events.
         'events: loop {
-            match next_event {
+            match pending_events.get(event_idx) {
                 // if the event is now (or before the block), match
                 // the event and handle its voice accordingly.
                 Some(event) if (event.timing() as usize) <= block_start => {
                     match event {
                         NoteEvent::NoteOn { .. } => {
-                            let note = event.note();
-                            voice_handler.start_voice(note);
+                            voice_handler
+                                .start_voice(event.note(), event.velocity());
                         }
                         NoteEvent::NoteOff { .. } => {
                             unimplemented!("note off events are not implemented for this project");
                         }
                     }
 
-                    // then obtain the next event and loop again
-                    next_event = audio.note_receiver.try_recv().ok();
+                    // then move to the next event and loop again
+                    event_idx += 1;
                 }
                 // if the event exists within this block, set the next block
                 // to start at the event and continue processing the block
@@ -55,5 +67,11 @@ pub fn process(audio: &mut AudioModel, buffer: &mut Buffer) {
         block_end = (block_end + MAX_BLOCK_SIZE).min(buffer_len);
     }
 
+    // carry the remaining events over to the next buffer
+    audio.pending_events.drain(..event_idx);
+    for event in &mut audio.pending_events {
+        event.advance(buffer_len as u32);
+    }
+
     audio.set_callback_timer();
 }
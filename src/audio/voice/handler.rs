@@ -59,7 +59,7 @@ impl VoiceHandler {
 
             for (value_idx, sample_idx) in (block_start..block_end).enumerate()
             {
-                let amp = voice_amp_envelope[value_idx];
+                let amp = voice_amp_envelope[value_idx] * voice.velocity;
 
                 let out = voice.oscillator.process();
 
@@ -70,14 +70,15 @@ impl VoiceHandler {
         }
     }
 
-    /// Starts a new voice.
+    /// Starts a new voice at `velocity` (`0.0` to `1.0`).
     #[allow(clippy::missing_panics_doc)] // this function should not panic
-    pub fn start_voice(&mut self, note: f32) -> &mut Voice {
+    pub fn start_voice(&mut self, note: f32, velocity: f32) -> &mut Voice {
         let next_voice_id = self.next_voice_id();
 
         let new_voice = Voice {
             id: next_voice_id,
             note,
+            velocity: velocity.clamp(0.0, 1.0),
             envelope_data: Arc::clone(&self.envelope_data),
             envelope_idx: 0,
             sample_rate: Arc::clone(&self.sample_rate),
@@ -103,7 +104,7 @@ impl VoiceHandler {
         };
 
         *oldest_voice = Some(new_voice);
-        return oldest_voice.as_mut().unwrap();
+        oldest_voice.as_mut().unwrap()
     }
 
     /// Immediately terminates all active voices.
@@ -15,6 +15,8 @@ pub struct Voice {
     pub id: u64,
     /// The MIDI note of the voice.
     pub note: f32,
+    /// The loudness of the voice, from `0.0` to `1.0`.
+    pub velocity: f32,
 
     pub envelope_data: Arc<[f32]>,
 
@@ -36,6 +38,7 @@ impl Voice {
         Self {
             id,
             note,
+            velocity: 1.0,
             envelope_data: envelope_ref,
             envelope_idx: 0,
             oscillator: SineOsc::new(note_to_freq(note), sample_rate.lr()),
@@ -54,15 +57,10 @@ impl Voice {
         let rem = env_len - pos;
         let num_iters = rem.min(block_len);
 
-        for i in 0..num_iters {
-            block[i] = self.envelope_data[pos + i];
-        }
-
-        let excess = block_len - num_iters;
+        block[..num_iters]
+            .copy_from_slice(&self.envelope_data[pos..pos + num_iters]);
 
-        for i in 0..excess {
-            block[i] = 0.0;
-        }
+        block[num_iters..block_len].fill(0.0);
 
         self.envelope_idx += num_iters;
     }
@@ -27,10 +27,39 @@ impl NoteEvent {
             NoteEvent::NoteOff { data, .. } => data.note,
         }
     }
+
+    pub fn velocity(&self) -> f32 {
+        match self {
+            NoteEvent::NoteOn { data, .. }
+            | NoteEvent::NoteOff { data, .. } => data.velocity,
+        }
+    }
+
+    /// Moves the event `samples` earlier, such as when a buffer has been
+    /// processed before the event was reached.
+    pub fn advance(&mut self, samples: u32) {
+        match self {
+            NoteEvent::NoteOn { timing, .. }
+            | NoteEvent::NoteOff { timing, .. } => {
+                *timing = timing.saturating_sub(samples);
+            }
+        }
+    }
 }
 
 /// The data encoded into each note event.
 #[derive(Clone, Copy, Debug)]
 pub struct NoteEventData {
     pub note: f32,
+    /// The loudness of the note, from `0.0` to `1.0`.
+    pub velocity: f32,
+    /// How long after it is sent the note should play, in seconds.
+    pub delay: f32,
+}
+
+impl NoteEventData {
+    /// Creates note data for `note` at full velocity.
+    pub fn new(note: f32) -> Self {
+        Self { note, velocity: 1.0, delay: 0.0 }
+    }
 }
@@ -1,16 +1,28 @@
 //! Module for the app's state.
 
+use super::params::{Params, MAX_SEED};
 use super::*;
 use crate::audio::voice::NoteEventData;
 use crate::prelude::*;
-use crate::ui::shape::Sequence;
+use crate::sequencer::{
+    load_midi_groove, Clock, Groove, GrooveTemplate, Humaniser, ShapeTiming,
+};
+use crate::ui::panel::{Control, ControlPanel};
+use crate::ui::shape::{
+    parse_durations, parse_edges, parse_steps, BallSettings, CurveKind,
+    DriftSettings, GraphRule, MorphSettings, PathSettings, PhysicsSettings,
+    PlayDirection, PlayheadSettings, RandomSettings, Ratchet, Sequence,
+    SnapGrid, TickOffset, Trigger, MAX_TICKS, RHYTHM_LIBRARY,
+};
+use crate::ui::text_input::TextInput;
 use crate::ui::text_slider::TextSlider;
 use crate::{
     audio::{model::AudioModel, voice::NoteEvent},
     ui::default_text_layout,
 };
+use std::path::{Path, PathBuf};
 use std::sync::{
-    atomic::AtomicU32,
+    atomic::{AtomicBool, AtomicU32, Ordering::Relaxed},
     mpsc::{self, channel},
     Arc, Mutex,
 };
@@ -19,6 +31,35 @@ use std::time::Instant;
 
 const DEFAULT_BPM: f32 = 120.0;
 const DEFAULT_NUM_NODES: usize = 4;
+const DEFAULT_STATUS: &str = "Press \"R\" to reset shape";
+
+/// How the sequencer's shape is currently laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShapeLayout {
+    /// A regular polygon with one vertex per beat.
+    Regular,
+    /// A Euclidean rhythm, which follows the Euclidean parameters.
+    Euclidean,
+    /// A shape from the rhythm library, which follows the selected shape.
+    Library,
+    /// A path such as an ellipse or arc, which follows the path parameters.
+    Path,
+    /// A random shape, which follows the random shape parameters.
+    Random,
+    /// Any other layout, such as one solved from typed durations.
+    Custom,
+}
+
+/// What typed text is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextCommand {
+    /// Solving a shape from a list of durations.
+    Durations,
+    /// Laying out a shape from a step string.
+    Steps,
+    /// Setting the edges of the shape's graph.
+    Edges,
+}
 
 /// The app's global state.
 pub struct AppModel {
@@ -33,6 +74,9 @@ pub struct AppModel {
     audio_stream: Stream<AudioModel>,
     callback_timer: Arc<Atomic<f32>>,
 
+    /// The master clock, which every shape follows.
+    pub clock: Clock,
+
     /// The bounding rect for the shape sequencer.
     pub sequencer_rect: Rect,
     pub sequencer: Sequence,
@@ -44,7 +88,56 @@ pub struct AppModel {
     time_signature_param: Arc<AtomicU32>,
     time_signature_params_last: u32,
 
+    /// The paged sliders either side of the sequencer.
+    pub control_panel: ControlPanel,
+    /// The parameters set by the control panel.
+    params: Arc<Params>,
+
+    /// How the shape is currently laid out.
+    layout: ShapeLayout,
+    euclid_params_last: (u32, u32, u32),
+    library_index_last: u32,
+
+    groove_params_last: (f32, u32),
+    shape_timing_last: ShapeTiming,
+    playhead_index_last: u32,
+    playhead_settings_last: PlayheadSettings,
+    selected_node_last: usize,
+    trigger_last: Trigger,
+    ratchet_last: Ratchet,
+    curve_kind_last: CurveKind,
+    path_last: PathSettings,
+    random_last: RandomSettings,
+    drift_last: DriftSettings,
+    physics_last: PhysicsSettings,
+    morph_last: MorphSettings,
+    balls_last: BallSettings,
+    tick_grid_last: Option<u32>,
+    tick_count_last: u32,
+    tick_index_last: u32,
+    tick_offset_last: TickOffset,
+    sensor_radius_last: f32,
+    sensor_note_last: f32,
+    crossings_enabled_last: bool,
+    crossing_note_last: f32,
+    trigger_seed_last: u32,
+    /// The groove extracted from the last MIDI file dropped onto the window.
+    midi_groove: Option<Groove>,
+
+    /// The bounding rect of the message shown above the sequencer.
+    pub status_rect: Rect,
+    /// The message shown above the sequencer.
+    status: String,
+
+    /// The text input shown in place of the status message while typing.
+    pub text_input: TextInput,
+    text_command: Option<TextCommand>,
+    /// The system clipboard, kept so that copied text stays available.
+    clipboard: Option<arboard::Clipboard>,
+
     note_event_sender: Arc<mpsc::Sender<NoteEvent>>,
+    /// Set to restart the humaniser's random offsets from their seed.
+    humaniser_reset: Arc<AtomicBool>,
     note_data_receiver: Arc<Mutex<mpsc::Receiver<NoteEventData>>>,
 
     note_event_thread: thread::JoinHandle<()>,
@@ -61,6 +154,9 @@ impl AppModel {
             .size(800, 800)
             .resizable(false)
             .key_pressed(key_pressed)
+            .key_released(key_released)
+            .received_character(received_character)
+            .dropped_file(dropped_file)
             .view(super::view::view)
             .title("Rhythmic Shapes Demo")
             .msaa_samples(4)
@@ -75,6 +171,7 @@ impl AppModel {
         } = AudioSystem::build();
 
         let sequencer_rect = Rect::from_wh(pt2(650.0, 650.0));
+        let status_rect = Rect::from_xy_wh(pt2(0.0, 380.0), pt2(300.0, 40.0));
 
         let tempo_param = Arc::new(Atomic::new(DEFAULT_BPM));
         let time_signature_param =
@@ -85,19 +182,24 @@ impl AppModel {
         let (note_data_tx, note_data_rx) = channel();
         let note_data_receiver = Arc::new(Mutex::new(note_data_rx));
 
+        let params = Arc::new(Params::new());
+        // `params` is moved into the model, so the note event thread's
+        // handle is cloned first
+        let thread_params = Arc::clone(&params);
+        let humaniser_reset = Arc::new(AtomicBool::new(false));
+
         Self {
             win,
             input_data: InputData::default(),
 
             audio_stream,
 
+            clock: Clock::new(DEFAULT_BPM),
+
             sequencer_rect,
             sequencer: {
                 let sender = Arc::clone(&note_event_sender);
-                Sequence::new(
-                    sequencer_rect, note_data_tx, DEFAULT_NUM_NODES,
-                    DEFAULT_BPM,
-                )
+                Sequence::new(sequencer_rect, note_data_tx, DEFAULT_NUM_NODES)
             },
             tempo_ui: {
                 let param = Arc::clone(&tempo_param);
@@ -141,21 +243,75 @@ impl AppModel {
             time_signature_params_last: time_signature_param.lr(),
             time_signature_param,
 
+            control_panel: build_control_panel(&params),
+            layout: ShapeLayout::Regular,
+            euclid_params_last: params.euclid(),
+            library_index_last: params.library_index.lr(),
+            groove_params_last: params.groove(),
+            shape_timing_last: params.shape_timing(),
+            playhead_index_last: params.playhead_index.lr(),
+            playhead_settings_last: params.playhead_settings(),
+            selected_node_last: 0,
+            trigger_last: params.trigger(),
+            ratchet_last: params.ratchet(),
+            curve_kind_last: params.curve_kind(),
+            path_last: params.path(),
+            random_last: params.random(),
+            drift_last: params.drift(),
+            physics_last: params.physics(),
+            morph_last: params.morph(),
+            balls_last: params.balls(),
+            tick_grid_last: params.tick_grid(),
+            tick_count_last: params.tick_count.lr(),
+            tick_index_last: params.tick_index.lr(),
+            tick_offset_last: params.tick_offset(),
+            sensor_radius_last: params.sensor_radius.lr(),
+            sensor_note_last: params.sensor_note.lr(),
+            crossings_enabled_last: params.crossings_enabled.lr(),
+            crossing_note_last: params.crossing_note.lr(),
+            trigger_seed_last: params.trigger_seed.lr(),
+            midi_groove: None,
+            params,
+
+            status_rect,
+            status: DEFAULT_STATUS.to_string(),
+
+            text_input: TextInput::new(status_rect),
+            text_command: None,
+            clipboard: arboard::Clipboard::new().ok(),
+
             note_event_thread: {
                 let recv = Arc::clone(&note_data_receiver);
                 let timer = Arc::clone(&callback_timer);
                 let sr = Arc::clone(&sample_rate);
                 let sender = Arc::clone(&note_event_sender);
+                let params = thread_params;
+                let reset = Arc::clone(&humaniser_reset);
+
+                thread::spawn(move || {
+                    let mut humaniser = Humaniser::new(params.humanise());
+
+                    loop {
+                        if let Ok(guard) = recv.lock() {
+                            if let Ok(mut msg) = guard.recv() {
+                                humaniser.set_settings(params.humanise());
+                                if reset.swap(false, Relaxed) {
+                                    humaniser.reset();
+                                }
 
-                thread::spawn(move || loop {
-                    if let Ok(guard) = recv.lock() {
-                        if let Ok(msg) = guard.recv() {
-                            let timer = timer.lr();
-                            let samples_exact = timer * sr.lr();
-                            let timing = samples_exact.round() as u32
-                                % BUFFER_SIZE as u32;
-                            sender
-                                .send(NoteEvent::NoteOn { timing, data: msg });
+                                let delay =
+                                    msg.delay + humaniser.humanise(&mut msg);
+
+                                let timer = timer.lr();
+                                let samples_exact = timer * sr.lr();
+                                let timing = samples_exact.round() as u32
+                                    % BUFFER_SIZE as u32
+                                    + (delay * sr.lr()).round() as u32;
+                                sender.send(NoteEvent::NoteOn {
+                                    timing,
+                                    data: msg,
+                                });
+                            }
                         }
                     }
                 })
@@ -165,6 +321,7 @@ impl AppModel {
             callback_timer,
 
             note_event_sender,
+            humaniser_reset,
             note_data_receiver,
 
             frame_timer: Instant::now(),
@@ -198,13 +355,595 @@ impl AppModel {
     }
 
     pub fn update_sequencer_params(&mut self) {
-        self.sequencer.set_tempo(self.tempo_param.lr());
+        self.clock.set_tempo(self.tempo_param.lr());
+        self.sequencer.set_snap_grid(SnapGrid::from_index(
+            self.params.snap_grid.lr() as usize,
+        ));
+        self.sequencer.set_direction(PlayDirection::from_index(
+            self.params.play_direction.lr() as usize,
+        ));
+        self.sequencer.set_graph_rule(GraphRule::from_index(
+            self.params.graph_rule.lr() as usize,
+        ));
 
         let ts_param = self.time_signature_param.lr();
         if ts_param != self.time_signature_params_last {
             self.time_signature_params_last = ts_param;
+            self.layout = ShapeLayout::Regular;
             self.sequencer.set_num_nodes(ts_param as usize);
         }
+
+        let euclid_params = self.params.euclid();
+        if euclid_params != self.euclid_params_last {
+            self.euclid_params_last = euclid_params;
+
+            if self.layout == ShapeLayout::Euclidean {
+                self.apply_euclidean();
+            }
+        }
+
+        let library_index = self.params.library_index.lr();
+        if library_index != self.library_index_last {
+            self.library_index_last = library_index;
+
+            if self.layout == ShapeLayout::Library {
+                self.load_library_shape();
+            }
+            else if let Some(shape) = RHYTHM_LIBRARY.get(library_index as usize)
+            {
+                self.set_status(&format!("{} (press \"L\")", shape.name));
+            }
+        }
+
+        let path = self.params.path();
+        if path != self.path_last {
+            self.path_last = path;
+
+            if self.layout == ShapeLayout::Path {
+                self.apply_path();
+            }
+        }
+
+        let random = self.params.random();
+        if random != self.random_last && self.layout == ShapeLayout::Random {
+            self.apply_random();
+        }
+
+        let drift = self.params.drift();
+        if drift != self.drift_last {
+            self.drift_last = drift;
+            self.sequencer.set_drift(drift);
+        }
+
+        let physics = self.params.physics();
+        if physics != self.physics_last {
+            self.physics_last = physics;
+            self.sequencer.set_physics(physics);
+        }
+
+        let morph = self.params.morph();
+        if morph != self.morph_last {
+            self.morph_last = morph;
+            self.sequencer.set_morph(morph);
+        }
+
+        let balls = self.params.balls();
+        if balls != self.balls_last {
+            self.balls_last = balls;
+            self.sequencer.set_balls(balls);
+        }
+
+        let groove_params = self.params.groove();
+        if groove_params != self.groove_params_last {
+            self.groove_params_last = groove_params;
+            self.apply_groove();
+        }
+
+        let shape_timing = self.params.shape_timing();
+        if shape_timing != self.shape_timing_last {
+            self.shape_timing_last = shape_timing;
+            self.sequencer.set_timing(shape_timing);
+        }
+
+        let trigger_seed = self.params.trigger_seed.lr();
+        if trigger_seed != self.trigger_seed_last {
+            self.trigger_seed_last = trigger_seed;
+            self.sequencer.set_trigger_seed(trigger_seed);
+        }
+
+        let sensor_radius = self.params.sensor_radius.lr();
+        if sensor_radius != self.sensor_radius_last {
+            self.sensor_radius_last = sensor_radius;
+            self.sequencer.set_sensor_radius(sensor_radius);
+        }
+
+        let sensor_note = self.params.sensor_note.lr();
+        if sensor_note != self.sensor_note_last {
+            self.sensor_note_last = sensor_note;
+            self.sequencer.set_sensor_note(sensor_note);
+        }
+
+        let crossings_enabled = self.params.crossings_enabled.lr();
+        if crossings_enabled != self.crossings_enabled_last {
+            self.crossings_enabled_last = crossings_enabled;
+            self.sequencer.set_crossings_enabled(crossings_enabled);
+        }
+
+        let crossing_note = self.params.crossing_note.lr();
+        if crossing_note != self.crossing_note_last {
+            self.crossing_note_last = crossing_note;
+            self.sequencer.set_crossing_note(crossing_note);
+        }
+
+        let tick_grid = self.params.tick_grid();
+        if tick_grid != self.tick_grid_last {
+            self.tick_grid_last = tick_grid;
+            self.sequencer.set_tick_grid(tick_grid);
+        }
+
+        self.update_freehand();
+        self.update_playhead_params();
+        self.update_node_params();
+    }
+
+    /// Applies the trigger, ratchet, curve and tick controls to the selected
+    /// node, or shows the settings of a newly-selected node on them.
+    fn update_node_params(&mut self) {
+        let idx = self.sequencer.selected_node();
+
+        // the node's settings may also have been reset by a new layout
+        let is_outdated = self.sequencer.trigger(idx)
+            != Some(self.trigger_last)
+            || self.sequencer.ratchet(idx) != Some(self.ratchet_last)
+            || self.sequencer.curve_kind(idx) != Some(self.curve_kind_last)
+            || self.sequencer.ticks(idx).map(|t| t.count)
+                != Some(self.tick_count_last);
+
+        if idx != self.selected_node_last || is_outdated {
+            self.select_node(idx);
+            return;
+        }
+
+        let trigger = self.params.trigger();
+        if trigger != self.trigger_last {
+            self.trigger_last = trigger;
+            self.sequencer.set_trigger(idx, trigger);
+        }
+
+        let ratchet = self.params.ratchet();
+        if ratchet != self.ratchet_last {
+            self.ratchet_last = ratchet;
+            self.sequencer.set_ratchet(idx, ratchet);
+        }
+
+        let curve_kind = self.params.curve_kind();
+        if curve_kind != self.curve_kind_last {
+            self.curve_kind_last = curve_kind;
+            self.sequencer.set_curve_kind(idx, curve_kind);
+        }
+
+        let tick_index = self.params.tick_index.lr();
+        if tick_index != self.tick_index_last {
+            self.select_tick(idx, tick_index);
+            return;
+        }
+
+        let tick_count = self.params.tick_count.lr();
+        let tick_offset = self.params.tick_offset();
+        if tick_count != self.tick_count_last
+            || tick_offset != self.tick_offset_last
+        {
+            self.tick_count_last = tick_count;
+            self.tick_offset_last = tick_offset;
+
+            let mut ticks = self.sequencer.ticks(idx).unwrap_or_default();
+            ticks.count = tick_count;
+            ticks.offsets[(tick_index as usize).min(MAX_TICKS - 1)] =
+                tick_offset;
+            self.sequencer.set_ticks(idx, ticks);
+        }
+    }
+
+    /// Shows the trigger, ratchet, curve and ticks of the `idx`th node on
+    /// their controls.
+    fn select_node(&mut self, idx: usize) {
+        let trigger = self.sequencer.trigger(idx).unwrap_or_default();
+        let ratchet = self.sequencer.ratchet(idx).unwrap_or_default();
+        let curve_kind = self.sequencer.curve_kind(idx).unwrap_or_default();
+        let ticks = self.sequencer.ticks(idx).unwrap_or_default();
+
+        let values = [
+            (Control::TriggerProbability, trigger.probability * 100.0),
+            (Control::TriggerCondition, trigger.condition.index() as f32),
+            (Control::RatchetCount, ratchet.count as f32),
+            (Control::RatchetSpacing, ratchet.spacing.index() as f32),
+            (Control::RatchetRamp, ratchet.ramp * 100.0),
+            (Control::CurveKind, curve_kind.index() as f32),
+            (Control::TickCount, ticks.count as f32),
+        ];
+
+        for (control, value) in values {
+            if let Some(slider) = self.control_panel.slider_mut(control) {
+                slider.set_value(value);
+            }
+        }
+
+        self.selected_node_last = idx;
+        self.trigger_last = self.params.trigger();
+        self.ratchet_last = self.params.ratchet();
+        self.curve_kind_last = self.params.curve_kind();
+        self.tick_count_last = self.params.tick_count.lr();
+
+        self.select_tick(idx, self.params.tick_index.lr());
+    }
+
+    /// Shows the offset of the `tick_idx`th tick after the `idx`th node on
+    /// the tick controls.
+    fn select_tick(&mut self, idx: usize, tick_idx: u32) {
+        let ticks = self.sequencer.ticks(idx).unwrap_or_default();
+        let offset = ticks.offset(tick_idx as usize);
+
+        let values = [
+            (Control::TickPitch, offset.pitch),
+            (Control::TickVelocity, offset.velocity * 100.0),
+        ];
+
+        for (control, value) in values {
+            if let Some(slider) = self.control_panel.slider_mut(control) {
+                slider.set_value(value);
+            }
+        }
+
+        self.tick_index_last = tick_idx;
+        self.tick_offset_last = self.params.tick_offset();
+    }
+
+    /// Applies the playhead controls to the selected playhead, or shows the
+    /// settings of a newly-selected playhead on them.
+    fn update_playhead_params(&mut self) {
+        let num_playheads = self.sequencer.num_playheads() as u32;
+        let idx = self.params.playhead_index.lr();
+
+        // only existing playheads can be selected
+        if idx >= num_playheads {
+            self.select_playhead(num_playheads - 1);
+            return;
+        }
+
+        if idx != self.playhead_index_last {
+            self.select_playhead(idx);
+            return;
+        }
+
+        let settings = self.params.playhead_settings();
+        if settings != self.playhead_settings_last {
+            self.playhead_settings_last = settings;
+            self.sequencer.set_playhead_settings(idx as usize, settings);
+        }
+    }
+
+    /// Selects the `idx`th playhead, and shows its settings on the playhead
+    /// controls.
+    fn select_playhead(&mut self, idx: u32) {
+        let Some(settings) = self.sequencer.playhead_settings(idx as usize)
+        else {
+            return;
+        };
+
+        let values = [
+            (Control::PlayheadIndex, idx as f32),
+            (Control::PlayheadPhase, settings.phase * 100.0),
+            (Control::PlayheadRatioNum, settings.ratio.0 as f32),
+            (Control::PlayheadRatioDen, settings.ratio.1 as f32),
+            (Control::PlayheadTranspose, settings.transpose),
+        ];
+
+        for (control, value) in values {
+            if let Some(slider) = self.control_panel.slider_mut(control) {
+                slider.set_value(value);
+            }
+        }
+
+        self.playhead_index_last = idx;
+        self.playhead_settings_last = self.params.playhead_settings();
+    }
+
+    /// Adds a playhead to the shape and selects it.
+    pub fn add_playhead(&mut self) {
+        if let Some(idx) = self.sequencer.add_playhead() {
+            self.select_playhead(idx as u32);
+            self.set_status(&format!(
+                "Added playhead {} (shift-P to remove)",
+                idx + 1
+            ));
+        }
+        else {
+            self.set_status("No more playheads can be added");
+        }
+    }
+
+    /// Removes the last playhead added to the shape.
+    pub fn remove_playhead(&mut self) {
+        if self.sequencer.remove_playhead() {
+            let num = self.sequencer.num_playheads();
+            self.set_status(&format!("{num} playhead(s) left"));
+        }
+    }
+
+    /// Removes every sensor from the shape.
+    pub fn clear_sensors(&mut self) {
+        let num = self.sequencer.num_sensors();
+        self.sequencer.clear_sensors();
+        self.set_status(&format!("Removed {num} sensor(s)"));
+    }
+
+    /// Applies the groove template selected by the control panel.
+    fn apply_groove(&mut self) {
+        let (swing_amount, idx) = self.params.groove();
+        let template = GrooveTemplate::from_index(idx as usize);
+
+        if template == GrooveTemplate::Midi && self.midi_groove.is_none() {
+            self.set_status("Drop a MIDI file to use its groove");
+        }
+
+        self.sequencer.set_groove(
+            template.groove(swing_amount / 100.0, self.midi_groove.as_ref()),
+        );
+    }
+
+    /// Extracts the groove of the MIDI file at `path`, and selects it.
+    pub fn load_groove_file(&mut self, path: &Path) {
+        match load_midi_groove(path) {
+            Ok(groove) => {
+                self.midi_groove = Some(groove);
+
+                let idx = GrooveTemplate::Midi.index() as f32;
+                if let Some(slider) =
+                    self.control_panel.slider_mut(Control::GrooveTemplate)
+                {
+                    slider.set_value(idx);
+                }
+
+                self.apply_groove();
+                self.set_status("Loaded MIDI groove");
+            }
+            Err(msg) => self.set_status(&msg),
+        }
+    }
+
+    /// Resets the shape, the master clock and the playheads, and restarts the
+    /// humaniser's random offsets so the loop repeats exactly.
+    pub fn reset(&mut self) {
+        self.clock.reset();
+        self.sequencer.reset();
+        self.humaniser_reset.sr(true);
+    }
+
+    /// Lays the sequencer out as the Euclidean rhythm described by the
+    /// control panel, which it then follows until another layout is chosen.
+    pub fn apply_euclidean(&mut self) {
+        let (hits, steps, rotation) = self.params.euclid();
+
+        self.layout = ShapeLayout::Euclidean;
+        self.sequencer.set_euclidean(
+            hits as usize,
+            steps as usize,
+            rotation as usize,
+        );
+
+        let steps = steps.max(3);
+        self.set_status(&format!(
+            "E({}, {steps}) rotated by {}",
+            hits.min(steps),
+            rotation % steps
+        ));
+    }
+
+    /// Lays the sequencer out along the path described by the control panel,
+    /// which it then follows until another layout is chosen.
+    pub fn apply_path(&mut self) {
+        let path = self.params.path();
+
+        self.layout = ShapeLayout::Path;
+        self.sequencer.set_path(path);
+
+        self.set_status(&format!(
+            "{} with {} points",
+            path.kind.name(),
+            path.points
+        ));
+    }
+
+    /// Lays the sequencer out as the random shape described by the control
+    /// panel, which it then follows until another layout is chosen.
+    pub fn apply_random(&mut self) {
+        let random = self.params.random();
+        self.random_last = random;
+
+        match self.sequencer.set_random(random) {
+            Ok(()) => {
+                self.layout = ShapeLayout::Random;
+                self.set_status(&format!("Random shape #{}", random.seed));
+            }
+            Err(msg) => self.set_status(&msg),
+        }
+    }
+
+    /// Lays the sequencer out as a random shape with the next seed.
+    pub fn next_random(&mut self) {
+        let seed = (self.params.random_seed.lr() + 1) % (MAX_SEED + 1);
+
+        if let Some(slider) = self.control_panel.slider_mut(Control::RandomSeed)
+        {
+            slider.set_value(seed as f32);
+        }
+
+        self.apply_random();
+    }
+
+    /// Holds the drifting vertices where they are, or lets them carry on.
+    pub fn toggle_drift_frozen(&mut self) {
+        let frozen = !self.sequencer.is_drift_frozen();
+        self.sequencer.set_drift_frozen(frozen);
+
+        self.set_status(if frozen { "Drift frozen" } else { "Drift resumed" });
+    }
+
+    /// Stores the shape in the snapshot selected on the control panel, so it
+    /// can be morphed to or from.
+    pub fn store_snapshot(&mut self) {
+        let idx = self.params.morph_slot.lr() as usize;
+
+        match self.sequencer.store_snapshot(idx) {
+            Ok(num) => self.set_status(&format!(
+                "Stored snapshot {} ({num} vertices)",
+                idx + 1
+            )),
+            Err(msg) => self.set_status(&msg),
+        }
+    }
+
+    /// Lays the sequencer out as the shape selected in the rhythm library,
+    /// which it then follows until another layout is chosen.
+    pub fn load_library_shape(&mut self) {
+        let idx = self.params.library_index.lr() as usize;
+
+        if let Some(shape) = RHYTHM_LIBRARY.get(idx) {
+            self.layout = ShapeLayout::Library;
+            self.sequencer.load_shape(shape);
+            self.set_status(shape.name);
+        }
+    }
+
+    /// Opens the text input, whose text is used for `command` once entered.
+    fn open_text_input(&mut self, command: TextCommand) {
+        let prompt = match command {
+            TextCommand::Durations => "Durations: ",
+            TextCommand::Steps => "Steps: ",
+            TextCommand::Edges => "Edges: ",
+        };
+
+        self.text_command = Some(command);
+        self.text_input.open(prompt);
+    }
+
+    /// Closes the text input, using its text if `submit` is `true`.
+    fn close_text_input(&mut self, submit: bool) {
+        let text = self.text_input.close();
+        let Some(command) = self.text_command.take() else {
+            return;
+        };
+
+        if !submit {
+            return;
+        }
+
+        let result = match command {
+            TextCommand::Durations => self.solve_durations(&text),
+            TextCommand::Steps => self.apply_steps(&text),
+            TextCommand::Edges => self.apply_edges(&text),
+        };
+
+        if let Err(msg) = result {
+            self.set_status(&msg);
+        }
+    }
+
+    /// Switches between drawing a new shape freehand and editing the current
+    /// shape.
+    pub fn toggle_drawing(&mut self) {
+        let drawing = !self.sequencer.is_drawing();
+        self.sequencer.set_drawing(drawing);
+
+        if drawing {
+            self.set_status("Drag to draw a shape (press \"H\" to stop)");
+        }
+        else {
+            self.set_status("Stopped drawing");
+        }
+    }
+
+    /// Lays the sequencer out along the last freehand stroke, once it is
+    /// drawn.
+    fn update_freehand(&mut self) {
+        let Some(stroke) = self.sequencer.take_stroke() else {
+            return;
+        };
+
+        match self.sequencer.set_freehand(&stroke, self.params.freehand()) {
+            Ok(num) => {
+                self.layout = ShapeLayout::Custom;
+                self.set_status(&format!("Drew a shape with {num} vertices"));
+            }
+            Err(msg) => self.set_status(&msg),
+        }
+    }
+
+    /// Lays the sequencer out so the time between its vertices follows the
+    /// durations in `text`. See [`parse_durations()`] for the format.
+    fn solve_durations(&mut self, text: &str) -> Result<(), String> {
+        let durations = parse_durations(text)?;
+        self.sequencer
+            .set_durations(&durations, self.params.solve_options())?;
+
+        self.layout = ShapeLayout::Custom;
+        self.set_status(&format!("Solved {} durations", durations.len()));
+
+        Ok(())
+    }
+
+    /// Lays the sequencer out as the step string in `text`. See
+    /// [`parse_steps()`] for the format.
+    fn apply_steps(&mut self, text: &str) -> Result<(), String> {
+        let steps = parse_steps(text)?;
+        self.sequencer.set_steps(&steps);
+
+        self.layout = ShapeLayout::Custom;
+        self.set_status(&format!("Loaded {} steps", steps.len()));
+
+        Ok(())
+    }
+
+    /// Sets the edges the playheads follow in the `Graph` direction to those
+    /// in `text`. See [`parse_edges()`] for the format.
+    fn apply_edges(&mut self, text: &str) -> Result<(), String> {
+        let edges = parse_edges(text)?;
+        let num = edges.len();
+        self.sequencer.set_edges(edges)?;
+
+        if num == 0 {
+            self.set_status("Cleared edges");
+        }
+        else {
+            self.set_status(&format!("Set {num} edge(s)"));
+        }
+
+        Ok(())
+    }
+
+    /// Converts the shape into a step string at the resolution set by the
+    /// control panel, which is shown and copied to the clipboard.
+    pub fn export_steps(&mut self) {
+        let resolution = self.params.export_resolution.lr() as usize;
+        let steps = self.sequencer.to_step_string(resolution);
+
+        if set_clipboard_text(self.clipboard.as_mut(), &steps) {
+            self.set_status(&format!("Copied {steps}"));
+        }
+        else {
+            self.set_status(&steps);
+        }
+    }
+
+    /// Sets the message shown above the sequencer.
+    pub fn set_status(&mut self, status: &str) {
+        self.status.clear();
+        self.status.push_str(status);
+    }
+
+    /// Returns the message shown above the sequencer.
+    pub fn status(&self) -> &str {
+        &self.status
     }
 }
 
@@ -245,10 +984,68 @@ impl InputData {
     pub fn left_button_just_lifted(&self) -> bool {
         self.left_button_last && !self.is_left_clicked
     }
+
+    /// Returns `true` if the right mouse button was just pressed down.
+    pub fn right_button_just_clicked(&self) -> bool {
+        !self.right_button_last && self.is_right_clicked
+    }
 }
 
-fn key_pressed(_: &App, app_model: &mut AppModel, key: Key) {
-    if key == Key::R {
-        app_model.sequencer.reset();
+fn key_pressed(app: &App, app_model: &mut AppModel, key: Key) {
+    if app_model.text_input.is_active() {
+        let mods = app.keys.mods;
+
+        match key {
+            Key::V if mods.ctrl() || mods.logo() => {
+                let clipboard = app_model.clipboard.as_mut();
+                if let Some(text) = clipboard_text(clipboard) {
+                    app_model.text_input.push_str(&text);
+                }
+            }
+            Key::Return => app_model.close_text_input(true),
+            Key::Escape => app_model.close_text_input(false),
+            Key::Back => app_model.text_input.pop(),
+            _ => (),
+        }
+
+        return;
+    }
+
+    match key {
+        Key::R => app_model.reset(),
+        Key::E => app_model.apply_euclidean(),
+        Key::L => app_model.load_library_shape(),
+        Key::O => app_model.apply_path(),
+        Key::H => app_model.toggle_drawing(),
+        Key::N if app.keys.mods.shift() => app_model.apply_random(),
+        Key::N => app_model.next_random(),
+        Key::Z => app_model.toggle_drift_frozen(),
+        Key::M => app_model.store_snapshot(),
+        Key::D => app_model.open_text_input(TextCommand::Durations),
+        Key::S => app_model.open_text_input(TextCommand::Steps),
+        Key::G => app_model.open_text_input(TextCommand::Edges),
+        Key::X => app_model.export_steps(),
+        Key::F => app_model.sequencer.set_fill(true),
+        Key::C => app_model.clear_sensors(),
+        Key::P if app.keys.mods.shift() => app_model.remove_playhead(),
+        Key::P => app_model.add_playhead(),
+        Key::Tab => app_model.control_panel.next_page(),
+        _ => (),
+    }
+}
+
+fn key_released(_: &App, app_model: &mut AppModel, key: Key) {
+    if key == Key::F {
+        app_model.sequencer.set_fill(false);
+    }
+}
+
+fn received_character(_: &App, app_model: &mut AppModel, ch: char) {
+    if app_model.text_input.is_active() {
+        app_model.text_input.push(ch);
     }
 }
+
+fn dropped_file(_: &App, app_model: &mut AppModel, path: PathBuf) {
+    app_model.load_groove_file(&path);
+}
@@ -1,7 +1,18 @@
+use super::params::*;
 use super::*;
 use crate::audio::AudioContext;
 use crate::audio::{self, model::AudioModel, NoteEvent};
 use crate::prelude::*;
+use crate::sequencer::{GrooveTemplate, JitterDistribution};
+use crate::ui::panel::{Control, ControlPage, ControlPanel};
+use crate::ui::shape::{
+    CurveKind, DriftMode, Engine, GraphRule, PathKind, PlayDirection,
+    RatchetSpacing, SnapGrid, TrigCondition, DEFAULT_CROSSING_NOTE,
+    DEFAULT_SENSOR_NOTE, DEFAULT_SENSOR_RADIUS, MAX_BALLS, MAX_PLAYHEADS,
+    MAX_RATCHET_COUNT, MAX_SNAPSHOTS, MAX_TICKS, MAX_TICK_GRID,
+    NODE_CAPACITY, RHYTHM_LIBRARY,
+};
+use crate::ui::text_slider::TextSlider;
 use std::sync::{mpsc, Arc};
 use std::time::Instant;
 
@@ -47,3 +58,812 @@ impl AudioSystem {
         }
     }
 }
+
+/// Builds the control panel, whose sliders write to `params`.
+pub fn build_control_panel(params: &Params) -> ControlPanel {
+    ControlPanel::new()
+        .with_page(build_layout_page(params))
+        .with_page(build_timing_page(params))
+        .with_page(build_playheads_page(params))
+        .with_page(build_triggers_page(params))
+        .with_page(build_segment_page(params))
+        .with_page(build_sensors_page(params))
+        .with_page(build_path_page(params))
+        .with_page(build_random_page(params))
+        .with_page(build_drift_page(params))
+        .with_page(build_balls_page(params))
+        .with_page(build_physics_page(params))
+        .with_page(build_morph_page(params))
+        .with_page(build_humanise_page(params))
+}
+
+fn build_layout_page(params: &Params) -> ControlPage {
+    let max_steps = NODE_CAPACITY as f32;
+    let num_shapes = RHYTHM_LIBRARY.len() as f32;
+    let num_grids = SnapGrid::ALL.len() as f32;
+
+    ControlPage::new("Layout")
+        .with_slider(Control::EuclidHits, |rect| {
+            let param = Arc::clone(&params.euclid_hits);
+            TextSlider::new(0.0, rect)
+                .with_label("Hits")
+                .with_integer_rounding()
+                .with_output_range(1.0..=max_steps)
+                .with_default_value(DEFAULT_EUCLID_HITS as f32)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::EuclidSteps, |rect| {
+            let param = Arc::clone(&params.euclid_steps);
+            TextSlider::new(0.0, rect)
+                .with_label("Steps")
+                .with_integer_rounding()
+                .with_output_range(3.0..=max_steps)
+                .with_default_value(DEFAULT_EUCLID_STEPS as f32)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::EuclidRotation, |rect| {
+            let param = Arc::clone(&params.euclid_rotation);
+            TextSlider::new(0.0, rect)
+                .with_label("Rotate")
+                .with_integer_rounding()
+                .with_output_range(0.0..=max_steps - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::LibraryShape, |rect| {
+            let param = Arc::clone(&params.library_index);
+            TextSlider::new(0.0, rect)
+                .with_label("Shape")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_shapes - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.006)
+                .with_formatting_callback(|_, value| {
+                    format!("{}", value as usize + 1)
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::SnapGrid, |rect| {
+            let param = Arc::clone(&params.snap_grid);
+            TextSlider::new(0.0, rect)
+                .with_label("Snap")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_grids - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.008)
+                .with_formatting_callback(|_, value| {
+                    SnapGrid::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::SolveConvex, |rect| {
+            let param = Arc::clone(&params.solve_convex);
+            toggle_slider(rect, "Convex", true)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+        .with_slider(Control::SolveCentred, |rect| {
+            let param = Arc::clone(&params.solve_centred);
+            toggle_slider(rect, "Centre", true)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+        .with_slider(Control::ExportResolution, |rect| {
+            let param = Arc::clone(&params.export_resolution);
+            TextSlider::new(0.0, rect)
+                .with_label("Res.")
+                .with_integer_rounding()
+                .with_output_range(2.0..=max_steps)
+                .with_default_value(DEFAULT_EXPORT_RESOLUTION as f32)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+}
+
+fn build_timing_page(params: &Params) -> ControlPage {
+    let num_templates = GrooveTemplate::ALL.len() as f32;
+    let num_directions = PlayDirection::ALL.len() as f32;
+    let num_rules = GraphRule::ALL.len() as f32;
+
+    ControlPage::new("Timing")
+        .with_slider(Control::SwingAmount, |rect| {
+            let param = Arc::clone(&params.swing_amount);
+            TextSlider::new(0.0, rect)
+                .with_label("Swing")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(50.0..=75.0)
+                .with_default_value(DEFAULT_SWING_AMOUNT)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::GrooveTemplate, |rect| {
+            let param = Arc::clone(&params.groove_template);
+            TextSlider::new(0.0, rect)
+                .with_label("Groove")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_templates - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.008)
+                .with_formatting_callback(|_, value| {
+                    GrooveTemplate::from_index(value as usize).name()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PlayDirection, |rect| {
+            let param = Arc::clone(&params.play_direction);
+            TextSlider::new(0.0, rect)
+                .with_label("Dir.")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_directions - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.008)
+                .with_formatting_callback(|_, value| {
+                    PlayDirection::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::GraphRule, |rect| {
+            let param = Arc::clone(&params.graph_rule);
+            TextSlider::new(0.0, rect)
+                .with_label("Rule")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_rules - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    GraphRule::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::ShapeLoops, |rect| {
+            let param = Arc::clone(&params.shape_loops);
+            ratio_slider(rect, "Loops")
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::ShapeBars, |rect| {
+            let param = Arc::clone(&params.shape_bars);
+            ratio_slider(rect, "Bars")
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::ShapePhase, |rect| {
+            let param = Arc::clone(&params.shape_phase);
+            phase_slider(rect, "Start")
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_playheads_page(params: &Params) -> ControlPage {
+    ControlPage::new("Playheads")
+        .with_slider(Control::PlayheadIndex, |rect| {
+            let param = Arc::clone(&params.playhead_index);
+            TextSlider::new(0.0, rect)
+                .with_label("Head")
+                .with_integer_rounding()
+                .with_output_range(0.0..=MAX_PLAYHEADS as f32 - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    format!("{}", value as usize + 1)
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PlayheadPhase, |rect| {
+            let param = Arc::clone(&params.playhead_phase);
+            phase_slider(rect, "Phase")
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PlayheadRatioNum, |rect| {
+            let param = Arc::clone(&params.playhead_ratio_num);
+            ratio_slider(rect, "Num.")
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PlayheadRatioDen, |rect| {
+            let param = Arc::clone(&params.playhead_ratio_den);
+            ratio_slider(rect, "Den.")
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PlayheadTranspose, |rect| {
+            let param = Arc::clone(&params.playhead_transpose);
+            TextSlider::new(0.0, rect)
+                .with_label("Trans.")
+                .with_suffix("st")
+                .with_positive_value_prefix()
+                .with_integer_rounding()
+                .with_output_range(-24.0..=24.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_triggers_page(params: &Params) -> ControlPage {
+    let num_conditions = TrigCondition::COUNT as f32;
+
+    ControlPage::new("Triggers")
+        .with_slider(Control::TriggerProbability, |rect| {
+            let param = Arc::clone(&params.trigger_probability);
+            TextSlider::new(0.0, rect)
+                .with_label("Prob.")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(0.0..=100.0)
+                .with_default_value(100.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::TriggerCondition, |rect| {
+            let param = Arc::clone(&params.trigger_condition);
+            TextSlider::new(0.0, rect)
+                .with_label("Cond.")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_conditions - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.002)
+                .with_formatting_callback(|_, value| {
+                    TrigCondition::from_index(value as usize).name()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::TriggerSeed, |rect| {
+            let param = Arc::clone(&params.trigger_seed);
+            seed_slider(rect)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::RatchetCount, |rect| {
+            let param = Arc::clone(&params.ratchet_count);
+            TextSlider::new(0.0, rect)
+                .with_label("Ratch.")
+                .with_integer_rounding()
+                .with_output_range(1.0..=MAX_RATCHET_COUNT as f32)
+                .with_default_value(1.0)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::RatchetSpacing, |rect| {
+            let param = Arc::clone(&params.ratchet_spacing);
+            let num_spacings = RatchetSpacing::ALL.len() as f32;
+            TextSlider::new(0.0, rect)
+                .with_label("Space")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_spacings - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.002)
+                .with_formatting_callback(|_, value| {
+                    RatchetSpacing::from_index(value as usize).name()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::RatchetRamp, |rect| {
+            let param = Arc::clone(&params.ratchet_ramp);
+            TextSlider::new(0.0, rect)
+                .with_label("Ramp")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(-100.0..=100.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_segment_page(params: &Params) -> ControlPage {
+    ControlPage::new("Segment")
+        .with_slider(Control::CurveKind, |rect| {
+            let param = Arc::clone(&params.curve_kind);
+            let num_kinds = CurveKind::ALL.len() as f32;
+            TextSlider::new(0.0, rect)
+                .with_label("Curve")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_kinds - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    CurveKind::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::TickGrid, |rect| {
+            let param = Arc::clone(&params.tick_grid);
+            TextSlider::new(0.0, rect)
+                .with_label("Grid")
+                .with_integer_rounding()
+                .with_output_range(0.0..=MAX_TICK_GRID as f32)
+                .with_default_value(0.0)
+                .with_sensitivity(0.002)
+                .with_formatting_callback(|_, value| {
+                    if value < 0.5 {
+                        "Off".to_string()
+                    }
+                    else {
+                        format!("{value:.0}")
+                    }
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::TickCount, |rect| {
+            let param = Arc::clone(&params.tick_count);
+            TextSlider::new(0.0, rect)
+                .with_label("Count")
+                .with_integer_rounding()
+                .with_output_range(0.0..=MAX_TICKS as f32)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::TickIndex, |rect| {
+            let param = Arc::clone(&params.tick_index);
+            TextSlider::new(0.0, rect)
+                .with_label("Tick")
+                .with_integer_rounding()
+                .with_output_range(0.0..=MAX_TICKS as f32 - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_formatting_callback(|_, value| {
+                    format!("{}", value as usize + 1)
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::TickPitch, |rect| {
+            let param = Arc::clone(&params.tick_pitch);
+            TextSlider::new(0.0, rect)
+                .with_label("Pitch")
+                .with_suffix(" st")
+                .with_integer_rounding()
+                .with_output_range(-24.0..=24.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::TickVelocity, |rect| {
+            let param = Arc::clone(&params.tick_velocity);
+            TextSlider::new(0.0, rect)
+                .with_label("Vel.")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(0.0..=100.0)
+                .with_default_value(100.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_sensors_page(params: &Params) -> ControlPage {
+    ControlPage::new("Sensors")
+        .with_slider(Control::SensorRadius, |rect| {
+            let param = Arc::clone(&params.sensor_radius);
+            TextSlider::new(0.0, rect)
+                .with_label("Radius")
+                .with_suffix(" px")
+                .with_integer_rounding()
+                .with_output_range(5.0..=150.0)
+                .with_default_value(DEFAULT_SENSOR_RADIUS)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::SensorNote, |rect| {
+            let param = Arc::clone(&params.sensor_note);
+            TextSlider::new(0.0, rect)
+                .with_label("Note")
+                .with_integer_rounding()
+                .with_output_range(24.0..=108.0)
+                .with_default_value(DEFAULT_SENSOR_NOTE)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::CrossingsEnabled, |rect| {
+            let param = Arc::clone(&params.crossings_enabled);
+            toggle_slider(rect, "Cross", false)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+        .with_slider(Control::CrossingNote, |rect| {
+            let param = Arc::clone(&params.crossing_note);
+            TextSlider::new(0.0, rect)
+                .with_label("X Note")
+                .with_integer_rounding()
+                .with_output_range(24.0..=108.0)
+                .with_default_value(DEFAULT_CROSSING_NOTE)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_path_page(params: &Params) -> ControlPage {
+    let num_kinds = PathKind::ALL.len() as f32;
+    let max_points = NODE_CAPACITY as f32 - 1.0;
+
+    ControlPage::new("Path")
+        .with_slider(Control::PathKind, |rect| {
+            let param = Arc::clone(&params.path_kind);
+            TextSlider::new(0.0, rect)
+                .with_label("Path")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_kinds - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    PathKind::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PathPoints, |rect| {
+            let param = Arc::clone(&params.path_points);
+            TextSlider::new(0.0, rect)
+                .with_label("Points")
+                .with_integer_rounding()
+                .with_output_range(3.0..=max_points)
+                .with_default_value(8.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::PathWidth, |rect| {
+            let param = Arc::clone(&params.path_width);
+            TextSlider::new(0.0, rect)
+                .with_label("Width")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(10.0..=100.0)
+                .with_default_value(80.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PathHeight, |rect| {
+            let param = Arc::clone(&params.path_height);
+            TextSlider::new(0.0, rect)
+                .with_label("Height")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(10.0..=100.0)
+                .with_default_value(80.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PathStart, |rect| {
+            let param = Arc::clone(&params.path_start);
+            TextSlider::new(0.0, rect)
+                .with_label("Start")
+                .with_suffix("°")
+                .with_integer_rounding()
+                .with_output_range(0.0..=359.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PathSpan, |rect| {
+            let param = Arc::clone(&params.path_span);
+            TextSlider::new(0.0, rect)
+                .with_label("Span")
+                .with_suffix("°")
+                .with_integer_rounding()
+                .with_output_range(30.0..=330.0)
+                .with_default_value(270.0)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::FreehandPoints, |rect| {
+            let param = Arc::clone(&params.freehand_points);
+            TextSlider::new(0.0, rect)
+                .with_label("Draw")
+                .with_integer_rounding()
+                .with_output_range(3.0..=NODE_CAPACITY as f32)
+                .with_default_value(8.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::FreehandDense, |rect| {
+            let param = Arc::clone(&params.freehand_dense);
+            toggle_slider(rect, "Dense", false)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+}
+
+fn build_random_page(params: &Params) -> ControlPage {
+    ControlPage::new("Random")
+        .with_slider(Control::RandomSeed, |rect| {
+            let param = Arc::clone(&params.random_seed);
+            seed_slider(rect)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::RandomPoints, |rect| {
+            let param = Arc::clone(&params.random_points);
+            TextSlider::new(0.0, rect)
+                .with_label("Verts")
+                .with_integer_rounding()
+                .with_output_range(3.0..=16.0)
+                .with_default_value(6.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::RandomConvex, |rect| {
+            let param = Arc::clone(&params.random_convex);
+            toggle_slider(rect, "Convex", false)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+        .with_slider(Control::RandomMinLength, |rect| {
+            let param = Arc::clone(&params.random_min_length);
+            TextSlider::new(0.0, rect)
+                .with_label("Min")
+                .with_suffix(" px")
+                .with_integer_rounding()
+                .with_output_range(0.0..=200.0)
+                .with_default_value(DEFAULT_RANDOM_MIN_LENGTH)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::RandomSnap, |rect| {
+            let param = Arc::clone(&params.random_snap);
+            toggle_slider(rect, "Snap", false)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+}
+
+fn build_drift_page(params: &Params) -> ControlPage {
+    let num_modes = DriftMode::ALL.len() as f32;
+
+    ControlPage::new("Drift")
+        .with_slider(Control::DriftMode, |rect| {
+            let param = Arc::clone(&params.drift_mode);
+            TextSlider::new(0.0, rect)
+                .with_label("Drift")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_modes - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    DriftMode::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::DriftRate, |rect| {
+            let param = Arc::clone(&params.drift_rate);
+            TextSlider::new(0.0, rect)
+                .with_label("Rate")
+                .with_output_range(0.01..=1.0)
+                .with_default_value(DEFAULT_DRIFT_RATE)
+                .with_sensitivity(0.002)
+                .with_formatting_callback(|_, value| format!("{value:.2} Hz"))
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::DriftDepth, |rect| {
+            let param = Arc::clone(&params.drift_depth);
+            TextSlider::new(0.0, rect)
+                .with_label("Depth")
+                .with_suffix(" px")
+                .with_integer_rounding()
+                .with_output_range(0.0..=150.0)
+                .with_default_value(DEFAULT_DRIFT_DEPTH)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_balls_page(params: &Params) -> ControlPage {
+    let num_engines = Engine::ALL.len() as f32;
+
+    ControlPage::new("Balls")
+        .with_slider(Control::Engine, |rect| {
+            let param = Arc::clone(&params.engine);
+            TextSlider::new(0.0, rect)
+                .with_label("Engine")
+                .with_integer_rounding()
+                .with_output_range(0.0..=num_engines - 1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.01)
+                .with_formatting_callback(|_, value| {
+                    Engine::from_index(value as usize).name().to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::BallCount, |rect| {
+            let param = Arc::clone(&params.ball_count);
+            TextSlider::new(0.0, rect)
+                .with_label("Balls")
+                .with_integer_rounding()
+                .with_output_range(1.0..=MAX_BALLS as f32)
+                .with_default_value(1.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::BallSpeed, |rect| {
+            let param = Arc::clone(&params.ball_speed);
+            TextSlider::new(0.0, rect)
+                .with_label("Speed")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(10.0..=400.0)
+                .with_default_value(100.0)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_physics_page(params: &Params) -> ControlPage {
+    ControlPage::new("Physics")
+        .with_slider(Control::PhysicsEnabled, |rect| {
+            let param = Arc::clone(&params.physics_enabled);
+            toggle_slider(rect, "Phys.", false)
+                .with_callback(move |_, value| param.sr(value > 0.5))
+        })
+        .with_slider(Control::PhysicsStiffness, |rect| {
+            let param = Arc::clone(&params.physics_stiffness);
+            TextSlider::new(0.0, rect)
+                .with_label("Spring")
+                .with_integer_rounding()
+                .with_output_range(1.0..=200.0)
+                .with_default_value(DEFAULT_PHYSICS_STIFFNESS)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PhysicsGravity, |rect| {
+            let param = Arc::clone(&params.physics_gravity);
+            TextSlider::new(0.0, rect)
+                .with_label("Grav.")
+                .with_integer_rounding()
+                .with_output_range(0.0..=1000.0)
+                .with_default_value(DEFAULT_PHYSICS_GRAVITY)
+                .with_sensitivity(0.002)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::PhysicsDamping, |rect| {
+            let param = Arc::clone(&params.physics_damping);
+            TextSlider::new(0.0, rect)
+                .with_label("Damp")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(0.0..=99.0)
+                .with_default_value(DEFAULT_PHYSICS_DAMPING)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+}
+
+fn build_morph_page(params: &Params) -> ControlPage {
+    ControlPage::new("Morph")
+        .with_slider(Control::MorphSlot, |rect| {
+            let param = Arc::clone(&params.morph_slot);
+            snapshot_slider(rect, "Slot", 0)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::MorphFrom, |rect| {
+            let param = Arc::clone(&params.morph_from);
+            snapshot_slider(rect, "From", 0)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::MorphTo, |rect| {
+            let param = Arc::clone(&params.morph_to);
+            snapshot_slider(rect, "To", 1)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::MorphAmount, |rect| {
+            let param = Arc::clone(&params.morph_amount);
+            TextSlider::new(0.0, rect)
+                .with_label("Morph")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(0.0..=100.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::MorphBars, |rect| {
+            let param = Arc::clone(&params.morph_bars);
+            TextSlider::new(0.0, rect)
+                .with_label("Bars")
+                .with_integer_rounding()
+                .with_output_range(0.0..=16.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_formatting_callback(|_, value| {
+                    if value < 0.5 {
+                        "Off".to_string()
+                    }
+                    else {
+                        format!("{value:.0}")
+                    }
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+}
+
+fn build_humanise_page(params: &Params) -> ControlPage {
+    ControlPage::new("Humanise")
+        .with_slider(Control::HumaniseTiming, |rect| {
+            let param = Arc::clone(&params.humanise_timing);
+            TextSlider::new(0.0, rect)
+                .with_label("Jitter")
+                .with_suffix("ms")
+                .with_integer_rounding()
+                .with_output_range(0.0..=MAX_HUMANISE_TIMING_MS)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::HumaniseDistribution, |rect| {
+            let param = Arc::clone(&params.humanise_distribution);
+            TextSlider::new(0.0, rect)
+                .with_label("Dist.")
+                .with_integer_rounding()
+                .with_output_range(0.0..=1.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.02)
+                .with_formatting_callback(|_, value| {
+                    JitterDistribution::from_index(value as usize)
+                        .name()
+                        .to_string()
+                })
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+        .with_slider(Control::HumaniseVelocity, |rect| {
+            let param = Arc::clone(&params.humanise_velocity);
+            TextSlider::new(0.0, rect)
+                .with_label("Vel.")
+                .with_suffix("%")
+                .with_integer_rounding()
+                .with_output_range(0.0..=100.0)
+                .with_default_value(0.0)
+                .with_sensitivity(0.004)
+                .with_callback(move |_, value| param.sr(value))
+        })
+        .with_slider(Control::HumaniseSeed, |rect| {
+            let param = Arc::clone(&params.humanise_seed);
+            seed_slider(rect)
+                .with_callback(move |_, value| param.sr(value as u32))
+        })
+}
+
+/// Builds a slider for the seed of a random number generator.
+fn seed_slider(rect: Rect) -> TextSlider {
+    TextSlider::new(0.0, rect)
+        .with_label("Seed")
+        .with_integer_rounding()
+        .with_output_range(0.0..=MAX_SEED as f32)
+        .with_default_value(0.0)
+        .with_sensitivity(0.002)
+}
+
+/// Builds a slider for a phase offset, as a percentage of a loop.
+fn phase_slider(rect: Rect, label: &str) -> TextSlider {
+    TextSlider::new(0.0, rect)
+        .with_label(label)
+        .with_suffix("%")
+        .with_integer_rounding()
+        .with_output_range(0.0..=99.0)
+        .with_default_value(0.0)
+        .with_sensitivity(0.003)
+}
+
+/// Builds a slider for one side of a speed ratio, from 1 to 8.
+fn ratio_slider(rect: Rect, label: &str) -> TextSlider {
+    TextSlider::new(0.0, rect)
+        .with_label(label)
+        .with_integer_rounding()
+        .with_output_range(1.0..=8.0)
+        .with_default_value(1.0)
+        .with_sensitivity(0.006)
+}
+
+/// Builds a slider which selects one of the shape's snapshots, shown
+/// counting from 1.
+fn snapshot_slider(rect: Rect, label: &str, default: usize) -> TextSlider {
+    TextSlider::new(0.0, rect)
+        .with_label(label)
+        .with_integer_rounding()
+        .with_output_range(0.0..=MAX_SNAPSHOTS as f32 - 1.0)
+        .with_default_value(default as f32)
+        .with_sensitivity(0.01)
+        .with_formatting_callback(|_, value| format!("{}", value as usize + 1))
+}
+
+/// Builds a slider which switches between "Off" and "On".
+fn toggle_slider(rect: Rect, label: &str, default: bool) -> TextSlider {
+    TextSlider::new(0.0, rect)
+        .with_label(label)
+        .with_integer_rounding()
+        .with_output_range(0.0..=1.0)
+        .with_default_value(if default { 1.0 } else { 0.0 })
+        .with_sensitivity(0.02)
+        .with_formatting_callback(|_, value| {
+            if value > 0.5 { "On" } else { "Off" }.to_string()
+        })
+}
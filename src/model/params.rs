@@ -1 +1,448 @@
 //! Module for app parameters.
+
+use crate::prelude::*;
+use crate::sequencer::{HumaniseSettings, JitterDistribution, ShapeTiming};
+use crate::ui::shape::{
+    BallSettings, CurveKind, DriftMode, DriftSettings, Engine,
+    FreehandSettings, MorphSettings, PathKind, PathSettings, PhysicsSettings,
+    PlayheadSettings, RandomSettings, Ratchet, RatchetSpacing, SolveOptions,
+    TickOffset, TrigCondition, Trigger, DEFAULT_CROSSING_NOTE,
+    DEFAULT_SENSOR_NOTE, DEFAULT_SENSOR_RADIUS, MAX_PATH_RADIUS,
+};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32},
+    Arc,
+};
+
+pub const DEFAULT_DRIFT_DEPTH: f32 = 30.0;
+pub const DEFAULT_DRIFT_RATE: f32 = 0.1;
+pub const DEFAULT_EUCLID_HITS: u32 = 3;
+pub const DEFAULT_EUCLID_STEPS: u32 = 8;
+pub const DEFAULT_EXPORT_RESOLUTION: u32 = 16;
+pub const DEFAULT_PHYSICS_DAMPING: f32 = 50.0;
+pub const DEFAULT_PHYSICS_GRAVITY: f32 = 200.0;
+pub const DEFAULT_PHYSICS_STIFFNESS: f32 = 40.0;
+pub const DEFAULT_RANDOM_MIN_LENGTH: f32 = 40.0;
+pub const DEFAULT_SWING_AMOUNT: f32 = 50.0;
+pub const MAX_HUMANISE_TIMING_MS: f32 = 50.0;
+pub const MAX_SEED: u32 = 999;
+
+/// Parameters which are set by the control panel's sliders.
+pub struct Params {
+    /// The number of hits in the Euclidean rhythm.
+    pub euclid_hits: Arc<AtomicU32>,
+    /// The number of steps in the Euclidean rhythm.
+    pub euclid_steps: Arc<AtomicU32>,
+    /// The number of steps the Euclidean rhythm is rotated by.
+    pub euclid_rotation: Arc<AtomicU32>,
+
+    /// The index of the selected shape in the rhythm library.
+    pub library_index: Arc<AtomicU32>,
+    /// The index of the grid which dragged nodes are locked to.
+    pub snap_grid: Arc<AtomicU32>,
+
+    /// Whether shapes solved from typed durations are convex.
+    pub solve_convex: Arc<AtomicBool>,
+    /// Whether shapes solved from typed durations are centred.
+    pub solve_centred: Arc<AtomicBool>,
+
+    /// The number of steps per loop when exporting the shape as text.
+    pub export_resolution: Arc<AtomicU32>,
+
+    /// The swing amount of the swing groove templates, as a percentage.
+    pub swing_amount: Arc<Atomic<f32>>,
+    /// The index of the selected groove template.
+    pub groove_template: Arc<AtomicU32>,
+    /// The index of the direction the playhead travels in.
+    pub play_direction: Arc<AtomicU32>,
+    /// The index of the rule playheads choose edges by in the `Graph`
+    /// direction.
+    pub graph_rule: Arc<AtomicU32>,
+    /// The number of loops the shape plays for every `shape_bars` bars.
+    pub shape_loops: Arc<AtomicU32>,
+    /// See `shape_loops`.
+    pub shape_bars: Arc<AtomicU32>,
+    /// How far through its loop the shape starts, as a percentage.
+    pub shape_phase: Arc<Atomic<f32>>,
+
+    /// The index of the playhead edited by the playhead controls.
+    pub playhead_index: Arc<AtomicU32>,
+    /// The selected playhead's phase offset, as a percentage of a loop.
+    pub playhead_phase: Arc<Atomic<f32>>,
+    /// The number of loops the selected playhead travels for every
+    /// `playhead_ratio_den` loops of the shared clock.
+    pub playhead_ratio_num: Arc<AtomicU32>,
+    /// See `playhead_ratio_num`.
+    pub playhead_ratio_den: Arc<AtomicU32>,
+    /// The selected playhead's transposition, in semitones.
+    pub playhead_transpose: Arc<Atomic<f32>>,
+
+    /// The selected node's chance of playing, as a percentage.
+    pub trigger_probability: Arc<Atomic<f32>>,
+    /// The index of the selected node's trigger condition.
+    pub trigger_condition: Arc<AtomicU32>,
+    /// The seed of the trigger probabilities.
+    pub trigger_seed: Arc<AtomicU32>,
+    /// The number of notes in the selected node's ratchet.
+    pub ratchet_count: Arc<AtomicU32>,
+    /// The index of the selected node's ratchet spacing.
+    pub ratchet_spacing: Arc<AtomicU32>,
+    /// How the velocity of the selected node's ratchet changes, as a
+    /// percentage.
+    pub ratchet_ramp: Arc<Atomic<f32>>,
+
+    /// The index of the kind of path the shape is laid out along.
+    pub path_kind: Arc<AtomicU32>,
+    /// The number of vertices on the path.
+    pub path_points: Arc<AtomicU32>,
+    /// The horizontal radius of the path, as a percentage of the largest.
+    pub path_width: Arc<Atomic<f32>>,
+    /// The vertical radius of the path, as a percentage of the largest.
+    pub path_height: Arc<Atomic<f32>>,
+    /// The angle of the path's first vertex, in degrees.
+    pub path_start: Arc<Atomic<f32>>,
+    /// The angle an arc path covers, in degrees.
+    pub path_span: Arc<Atomic<f32>>,
+    /// The seed of the random shape.
+    pub random_seed: Arc<AtomicU32>,
+    /// The number of vertices of the random shape.
+    pub random_points: Arc<AtomicU32>,
+    /// Whether the random shape is convex.
+    pub random_convex: Arc<AtomicBool>,
+    /// The shortest length of any segment of the random shape, in pixels.
+    pub random_min_length: Arc<Atomic<f32>>,
+    /// Whether the random shape's segments follow the snap grid.
+    pub random_snap: Arc<AtomicBool>,
+
+    /// The index of the way the vertices wander over time.
+    pub drift_mode: Arc<AtomicU32>,
+    /// How quickly the vertices wander, in cycles per second.
+    pub drift_rate: Arc<Atomic<f32>>,
+    /// The furthest each vertex may wander, in pixels.
+    pub drift_depth: Arc<Atomic<f32>>,
+
+    /// The index of what plays the notes of the shape.
+    pub engine: Arc<AtomicU32>,
+    /// The number of balls.
+    pub ball_count: Arc<AtomicU32>,
+    /// The distance each ball travels in one loop, as a percentage of the
+    /// shape's perimeter.
+    pub ball_speed: Arc<Atomic<f32>>,
+
+    /// Whether the vertices move as masses joined by springs.
+    pub physics_enabled: Arc<AtomicBool>,
+    /// The stiffness of the springs along each segment.
+    pub physics_stiffness: Arc<Atomic<f32>>,
+    /// The downwards acceleration of the vertices, in pixels per second
+    /// squared.
+    pub physics_gravity: Arc<Atomic<f32>>,
+    /// The percentage of the vertices' speed lost each second.
+    pub physics_damping: Arc<Atomic<f32>>,
+
+    /// The index of the snapshot the shape is stored in.
+    pub morph_slot: Arc<AtomicU32>,
+    /// The index of the snapshot morphed from.
+    pub morph_from: Arc<AtomicU32>,
+    /// The index of the snapshot morphed to.
+    pub morph_to: Arc<AtomicU32>,
+    /// How far the shape is morphed, as a percentage.
+    pub morph_amount: Arc<Atomic<f32>>,
+    /// The number of bars a whole morph takes, or `0` for none.
+    pub morph_bars: Arc<AtomicU32>,
+
+    /// The number of vertices of a freehand shape which play a note.
+    pub freehand_points: Arc<AtomicU32>,
+    /// Whether freehand shapes keep extra vertices (as rests) to follow the
+    /// stroke closely.
+    pub freehand_dense: Arc<AtomicBool>,
+
+    /// The index of the kind of curve the segment after the selected node
+    /// follows.
+    pub curve_kind: Arc<AtomicU32>,
+
+    /// The number of steps in the grid of ticks around the shape, or `0` to
+    /// use each segment's own number of ticks.
+    pub tick_grid: Arc<AtomicU32>,
+    /// The number of ticks on the segment after the selected node.
+    pub tick_count: Arc<AtomicU32>,
+    /// The index of the selected tick on that segment.
+    pub tick_index: Arc<AtomicU32>,
+    /// The selected tick's transposition, in semitones.
+    pub tick_pitch: Arc<Atomic<f32>>,
+    /// The selected tick's relative velocity, as a percentage.
+    pub tick_velocity: Arc<Atomic<f32>>,
+
+    /// The distance within which a playhead triggers a sensor.
+    pub sensor_radius: Arc<Atomic<f32>>,
+    /// The MIDI note played by sensors.
+    pub sensor_note: Arc<Atomic<f32>>,
+
+    /// Whether notes are played where the shape crosses itself.
+    pub crossings_enabled: Arc<AtomicBool>,
+    /// The MIDI note played where the shape crosses itself.
+    pub crossing_note: Arc<Atomic<f32>>,
+
+    /// The largest random timing offset of each note, in milliseconds.
+    pub humanise_timing: Arc<Atomic<f32>>,
+    /// The index of the distribution of random timing offsets.
+    pub humanise_distribution: Arc<AtomicU32>,
+    /// The largest random velocity reduction of each note, as a percentage.
+    pub humanise_velocity: Arc<Atomic<f32>>,
+    /// The seed of the random offsets.
+    pub humanise_seed: Arc<AtomicU32>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self {
+            euclid_hits: Arc::new(AtomicU32::new(DEFAULT_EUCLID_HITS)),
+            euclid_steps: Arc::new(AtomicU32::new(DEFAULT_EUCLID_STEPS)),
+            euclid_rotation: Arc::new(AtomicU32::new(0)),
+
+            library_index: Arc::new(AtomicU32::new(0)),
+            snap_grid: Arc::new(AtomicU32::new(0)),
+
+            solve_convex: Arc::new(AtomicBool::new(true)),
+            solve_centred: Arc::new(AtomicBool::new(true)),
+
+            export_resolution: Arc::new(AtomicU32::new(
+                DEFAULT_EXPORT_RESOLUTION,
+            )),
+
+            swing_amount: Arc::new(Atomic::new(DEFAULT_SWING_AMOUNT)),
+            groove_template: Arc::new(AtomicU32::new(0)),
+            play_direction: Arc::new(AtomicU32::new(0)),
+            graph_rule: Arc::new(AtomicU32::new(0)),
+            shape_loops: Arc::new(AtomicU32::new(1)),
+            shape_bars: Arc::new(AtomicU32::new(1)),
+            shape_phase: Arc::new(Atomic::new(0.0)),
+
+            playhead_index: Arc::new(AtomicU32::new(0)),
+            playhead_phase: Arc::new(Atomic::new(0.0)),
+            playhead_ratio_num: Arc::new(AtomicU32::new(1)),
+            playhead_ratio_den: Arc::new(AtomicU32::new(1)),
+            playhead_transpose: Arc::new(Atomic::new(0.0)),
+
+            trigger_probability: Arc::new(Atomic::new(100.0)),
+            trigger_condition: Arc::new(AtomicU32::new(0)),
+            trigger_seed: Arc::new(AtomicU32::new(0)),
+            ratchet_count: Arc::new(AtomicU32::new(1)),
+            ratchet_spacing: Arc::new(AtomicU32::new(0)),
+            ratchet_ramp: Arc::new(Atomic::new(0.0)),
+
+            path_kind: Arc::new(AtomicU32::new(0)),
+            path_points: Arc::new(AtomicU32::new(8)),
+            path_width: Arc::new(Atomic::new(80.0)),
+            path_height: Arc::new(Atomic::new(80.0)),
+            path_start: Arc::new(Atomic::new(0.0)),
+            path_span: Arc::new(Atomic::new(270.0)),
+            random_seed: Arc::new(AtomicU32::new(0)),
+            random_points: Arc::new(AtomicU32::new(6)),
+            random_convex: Arc::new(AtomicBool::new(false)),
+            random_min_length: Arc::new(Atomic::new(DEFAULT_RANDOM_MIN_LENGTH)),
+            random_snap: Arc::new(AtomicBool::new(false)),
+
+            drift_mode: Arc::new(AtomicU32::new(0)),
+            drift_rate: Arc::new(Atomic::new(DEFAULT_DRIFT_RATE)),
+            drift_depth: Arc::new(Atomic::new(DEFAULT_DRIFT_DEPTH)),
+
+            engine: Arc::new(AtomicU32::new(0)),
+            ball_count: Arc::new(AtomicU32::new(1)),
+            ball_speed: Arc::new(Atomic::new(100.0)),
+
+            physics_enabled: Arc::new(AtomicBool::new(false)),
+            physics_stiffness: Arc::new(Atomic::new(DEFAULT_PHYSICS_STIFFNESS)),
+            physics_gravity: Arc::new(Atomic::new(DEFAULT_PHYSICS_GRAVITY)),
+            physics_damping: Arc::new(Atomic::new(DEFAULT_PHYSICS_DAMPING)),
+
+            morph_slot: Arc::new(AtomicU32::new(0)),
+            morph_from: Arc::new(AtomicU32::new(0)),
+            morph_to: Arc::new(AtomicU32::new(1)),
+            morph_amount: Arc::new(Atomic::new(0.0)),
+            morph_bars: Arc::new(AtomicU32::new(0)),
+
+            freehand_points: Arc::new(AtomicU32::new(8)),
+            freehand_dense: Arc::new(AtomicBool::new(false)),
+
+            curve_kind: Arc::new(AtomicU32::new(0)),
+
+            tick_grid: Arc::new(AtomicU32::new(0)),
+            tick_count: Arc::new(AtomicU32::new(0)),
+            tick_index: Arc::new(AtomicU32::new(0)),
+            tick_pitch: Arc::new(Atomic::new(0.0)),
+            tick_velocity: Arc::new(Atomic::new(100.0)),
+
+            sensor_radius: Arc::new(Atomic::new(DEFAULT_SENSOR_RADIUS)),
+            sensor_note: Arc::new(Atomic::new(DEFAULT_SENSOR_NOTE)),
+
+            crossings_enabled: Arc::new(AtomicBool::new(false)),
+            crossing_note: Arc::new(Atomic::new(DEFAULT_CROSSING_NOTE)),
+
+            humanise_timing: Arc::new(Atomic::new(0.0)),
+            humanise_distribution: Arc::new(AtomicU32::new(0)),
+            humanise_velocity: Arc::new(Atomic::new(0.0)),
+            humanise_seed: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Returns the Euclidean rhythm parameters as `(hits, steps, rotation)`.
+    pub fn euclid(&self) -> (u32, u32, u32) {
+        (
+            self.euclid_hits.lr(),
+            self.euclid_steps.lr(),
+            self.euclid_rotation.lr(),
+        )
+    }
+
+    /// Returns the options for solving shapes from typed durations.
+    pub fn solve_options(&self) -> SolveOptions {
+        SolveOptions {
+            convex: self.solve_convex.lr(),
+            centred: self.solve_centred.lr(),
+        }
+    }
+
+    /// Returns the groove parameters as `(swing amount, template index)`.
+    pub fn groove(&self) -> (f32, u32) {
+        (self.swing_amount.lr(), self.groove_template.lr())
+    }
+
+    /// Returns how the shape's loop lines up with the master clock.
+    pub fn shape_timing(&self) -> ShapeTiming {
+        ShapeTiming {
+            ratio: (self.shape_loops.lr(), self.shape_bars.lr()),
+            phase: self.shape_phase.lr() * 0.01,
+        }
+    }
+
+    /// Returns the settings of the selected playhead.
+    pub fn playhead_settings(&self) -> PlayheadSettings {
+        PlayheadSettings {
+            phase: self.playhead_phase.lr() * 0.01,
+            ratio: (self.playhead_ratio_num.lr(), self.playhead_ratio_den.lr()),
+            transpose: self.playhead_transpose.lr(),
+        }
+    }
+
+    /// Returns the trigger of the selected node.
+    pub fn trigger(&self) -> Trigger {
+        Trigger {
+            probability: self.trigger_probability.lr() * 0.01,
+            condition: TrigCondition::from_index(
+                self.trigger_condition.lr() as usize,
+            ),
+        }
+    }
+
+    /// Returns the ratchet of the selected node.
+    pub fn ratchet(&self) -> Ratchet {
+        Ratchet {
+            count: self.ratchet_count.lr(),
+            spacing: RatchetSpacing::from_index(
+                self.ratchet_spacing.lr() as usize,
+            ),
+            ramp: self.ratchet_ramp.lr() * 0.01,
+        }
+    }
+
+    /// Returns the settings of the path the shape is laid out along.
+    pub fn path(&self) -> PathSettings {
+        let radius = |param: &Atomic<f32>| param.lr() * 0.01 * MAX_PATH_RADIUS;
+
+        PathSettings {
+            kind: PathKind::from_index(self.path_kind.lr() as usize),
+            points: self.path_points.lr() as usize,
+            radii: vec2(radius(&self.path_width), radius(&self.path_height)),
+            start: self.path_start.lr(),
+            span: self.path_span.lr(),
+        }
+    }
+
+    /// Returns the settings of the random shape.
+    pub fn random(&self) -> RandomSettings {
+        RandomSettings {
+            seed: self.random_seed.lr(),
+            points: self.random_points.lr() as usize,
+            convex: self.random_convex.lr(),
+            min_length: self.random_min_length.lr(),
+            snap: self.random_snap.lr(),
+        }
+    }
+
+    /// Returns the settings for how the vertices wander over time.
+    pub fn drift(&self) -> DriftSettings {
+        DriftSettings {
+            mode: DriftMode::from_index(self.drift_mode.lr() as usize),
+            rate: self.drift_rate.lr(),
+            depth: self.drift_depth.lr(),
+        }
+    }
+
+    /// Returns the settings for what plays the notes of the shape.
+    pub fn balls(&self) -> BallSettings {
+        BallSettings {
+            engine: Engine::from_index(self.engine.lr() as usize),
+            count: self.ball_count.lr() as usize,
+            speed: self.ball_speed.lr() / 100.0,
+        }
+    }
+
+    /// Returns the settings of the physics simulation.
+    pub fn physics(&self) -> PhysicsSettings {
+        PhysicsSettings {
+            enabled: self.physics_enabled.lr(),
+            stiffness: self.physics_stiffness.lr(),
+            gravity: self.physics_gravity.lr(),
+            damping: self.physics_damping.lr() / 100.0,
+        }
+    }
+
+    /// Returns the settings for morphing between snapshots of the shape.
+    pub fn morph(&self) -> MorphSettings {
+        MorphSettings {
+            from: self.morph_from.lr() as usize,
+            to: self.morph_to.lr() as usize,
+            amount: self.morph_amount.lr() / 100.0,
+            bars: self.morph_bars.lr(),
+        }
+    }
+
+    /// Returns the settings for turning a freehand stroke into a shape.
+    pub fn freehand(&self) -> FreehandSettings {
+        FreehandSettings {
+            points: self.freehand_points.lr() as usize,
+            dense: self.freehand_dense.lr(),
+        }
+    }
+
+    /// Returns the kind of curve the segment after the selected node follows.
+    pub fn curve_kind(&self) -> CurveKind {
+        CurveKind::from_index(self.curve_kind.lr() as usize)
+    }
+
+    /// Returns the number of steps in the grid of ticks around the shape, if
+    /// it is enabled.
+    pub fn tick_grid(&self) -> Option<u32> {
+        Some(self.tick_grid.lr()).filter(|&steps| steps > 0)
+    }
+
+    /// Returns the offset of the selected tick.
+    pub fn tick_offset(&self) -> TickOffset {
+        TickOffset {
+            pitch: self.tick_pitch.lr(),
+            velocity: self.tick_velocity.lr() * 0.01,
+        }
+    }
+
+    /// Returns the settings for humanising notes.
+    pub fn humanise(&self) -> HumaniseSettings {
+        HumaniseSettings {
+            timing_ms: self.humanise_timing.lr(),
+            distribution: JitterDistribution::from_index(
+                self.humanise_distribution.lr() as usize,
+            ),
+            velocity: self.humanise_velocity.lr() * 0.01,
+            seed: self.humanise_seed.lr(),
+        }
+    }
+}
@@ -8,8 +8,13 @@ pub fn update(app: &App, model: &mut AppModel, update: Update) {
 
     model.tempo_ui.update(input_data);
     model.time_signature_ui.update(input_data);
+    model.control_panel.update(input_data);
+    model.text_input.update(input_data);
 
-    model.update_sequencer_params();
+    // the clock moves first, so timing changes line up with this frame's bar
+    model.clock.advance(model.input_data.delta_time);
+    model.sequencer.set_clock(&model.clock);
 
+    model.update_sequencer_params();
     model.sequencer.update(&model.input_data);
 }
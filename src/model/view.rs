@@ -8,9 +8,20 @@ pub fn view(app: &App, model: &AppModel, frame: Frame) {
     let frame = &frame;
     if frame.nth() == 0 {
         draw.background().color(WHITE);
-        draw.text("Press \"R\" to reset shape")
-            .xy(pt2(0.0, 380.0))
-            .wh(pt2(300.0, 50.0))
+    }
+
+    if model.text_input.is_active() {
+        model.text_input.draw(draw, frame);
+    }
+    else {
+        let status_rect = model.status_rect;
+        draw.rect()
+            .xy(status_rect.xy())
+            .wh(status_rect.wh())
+            .color(WHITE);
+        draw.text(model.status())
+            .xy(status_rect.xy())
+            .wh(status_rect.wh())
             .color(BLACK)
             .font_size(20);
     }
@@ -22,6 +33,7 @@ pub fn view(app: &App, model: &AppModel, frame: Frame) {
 
     model.tempo_ui.draw(draw, frame);
     model.time_signature_ui.draw(draw, frame);
+    model.control_panel.draw(draw, frame);
     model.sequencer.draw(draw, frame);
 
     _ = draw.to_frame(app, frame);
@@ -1,3 +1,13 @@
 //! Module for the time-based rhythmic sequencer.
 
 use crate::prelude::*;
+
+pub mod clock;
+pub mod groove;
+pub mod humanise;
+pub mod midi;
+
+pub use clock::{Clock, ShapeTiming};
+pub use groove::{Groove, GrooveTemplate};
+pub use humanise::{HumaniseSettings, Humaniser, JitterDistribution};
+pub use midi::load_midi_groove;
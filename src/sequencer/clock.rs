@@ -0,0 +1,80 @@
+//! Module for the master clock shared by every shape.
+
+use super::*;
+
+/// The number of beats in each bar of the master clock.
+const BEATS_PER_BAR: f32 = 4.0;
+
+/// The master clock, which counts bars of four beats at the app's tempo.
+///
+/// Shapes follow the clock's total time rather than counting time
+/// themselves, so they always stay locked together.
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    /// The time elapsed, in bars.
+    bars: f64,
+    /// The tempo in beats per minute (BPM).
+    tempo: f32,
+}
+
+impl Clock {
+    /// Creates a new `Clock` at `tempo` BPM.
+    pub fn new(tempo: f32) -> Self {
+        Self { bars: 0.0, tempo }
+    }
+
+    /// Sets the tempo of the clock.
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.tempo = bpm;
+    }
+
+    /// Moves the clock forward by `delta_time` seconds.
+    pub fn advance(&mut self, delta_time: f32) {
+        self.bars += (delta_time / self.secs_per_bar()) as f64;
+    }
+
+    /// Returns the length of a bar, in seconds.
+    pub fn secs_per_bar(&self) -> f32 {
+        60.0 / self.tempo * BEATS_PER_BAR
+    }
+
+    /// Moves the clock back to the start of the first bar.
+    pub fn reset(&mut self) {
+        self.bars = 0.0;
+    }
+
+    /// Returns the time elapsed, in bars.
+    pub fn bars(&self) -> f64 {
+        self.bars
+    }
+}
+
+/// How a shape's loop lines up with the master clock.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeTiming {
+    /// The shape's speed, as `(loops, bars)`: the shape plays `loops` loops
+    /// for every `bars` bars of the master clock.
+    pub ratio: (u32, u32),
+    /// How far through its loop the shape is when the clock starts (`0.0` to
+    /// `1.0`).
+    pub phase: f32,
+}
+
+impl Default for ShapeTiming {
+    fn default() -> Self {
+        Self { ratio: (1, 1), phase: 0.0 }
+    }
+}
+
+impl ShapeTiming {
+    /// Returns the number of loops the shape has played after `bars` bars of
+    /// the master clock.
+    pub fn loops_at(&self, bars: f64) -> f64 {
+        bars.mul_add(self.rate(), self.phase as f64)
+    }
+
+    /// Returns the number of loops the shape plays in each bar.
+    pub fn rate(&self) -> f64 {
+        self.ratio.0.max(1) as f64 / self.ratio.1.max(1) as f64
+    }
+}
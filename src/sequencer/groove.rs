@@ -0,0 +1,200 @@
+//! Module for swing and groove templates.
+
+use super::*;
+
+/// The largest amount (in steps) which a step may be moved by.
+const MAX_OFFSET: f32 = 0.9;
+
+/// A groove template, which moves each step of a grid earlier or later in
+/// time to warp the playhead's otherwise constant speed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Groove {
+    /// The offset of each step of the grid, as a fraction of a step. Positive
+    /// offsets are late, and negative offsets are early.
+    offsets: Vec<f32>,
+}
+
+impl Default for Groove {
+    fn default() -> Self {
+        Self::straight()
+    }
+}
+
+impl Groove {
+    /// A groove which doesn't move any steps.
+    pub fn straight() -> Self {
+        Self { offsets: vec![0.0] }
+    }
+
+    /// Creates a groove from the offset of each step of a grid which spans one
+    /// loop, in fractions of a step.
+    ///
+    /// The first step is always left in place, and steps are kept in order.
+    pub fn from_offsets(offsets: &[f32]) -> Self {
+        if offsets.is_empty() {
+            return Self::straight();
+        }
+
+        let mut offsets: Vec<f32> = offsets
+            .iter()
+            .map(|o| o.clamp(-MAX_OFFSET, MAX_OFFSET))
+            .collect();
+        offsets[0] = 0.0;
+
+        // no step may be moved past the one before it
+        for i in 1..offsets.len() {
+            offsets[i] = offsets[i].max(offsets[i - 1] - MAX_OFFSET);
+        }
+
+        Self { offsets }
+    }
+
+    /// Creates an MPC-style swing groove, where every second step of a grid
+    /// with `steps_per_loop` steps is delayed so that it lands `amount` of
+    /// the way through each pair of steps.
+    ///
+    /// `amount` is clamped between `0.5` (straight) and `0.75` (dotted).
+    pub fn swing(steps_per_loop: usize, amount: f32) -> Self {
+        let offset = amount.clamp(0.5, 0.75).mul_add(2.0, -1.0);
+        let offsets: Vec<f32> = (0..steps_per_loop.max(1))
+            .map(|i| if i % 2 == 1 { offset } else { 0.0 })
+            .collect();
+
+        Self::from_offsets(&offsets)
+    }
+
+    /// Returns whether the groove moves any steps.
+    pub fn is_straight(&self) -> bool {
+        self.offsets.iter().all(|o| epsilon_eq(*o, 0.0))
+    }
+
+    /// Maps time through a loop (`0.0` to `1.0`) to the distance travelled
+    /// around the shape (also `0.0` to `1.0`).
+    ///
+    /// Each step of the grid is reached at its offset time, and the playhead
+    /// moves at a constant speed between steps.
+    pub fn warp(&self, time: f32) -> f32 {
+        if self.is_straight() {
+            return time;
+        }
+
+        let num = self.offsets.len();
+        let n = num as f32;
+        let step_time = |i: usize| {
+            if i >= num {
+                1.0
+            }
+            else {
+                (i as f32 + self.offsets[i]) / n
+            }
+        };
+
+        let idx = (0..num).rfind(|&i| step_time(i) <= time).unwrap_or(0);
+        let interp = ilerp(step_time(idx), step_time(idx + 1), time);
+
+        ((idx as f32 + interp.clamp(0.0, 1.0)) / n).clamp(0.0, 1.0)
+    }
+}
+
+/// The groove templates which can be selected from the UI.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrooveTemplate {
+    /// Sixteenth-note swing by the global swing amount.
+    Swing16,
+    /// Eighth-note swing by the global swing amount.
+    Swing8,
+    /// Sixteenth-note swing by a fixed MPC-style percentage.
+    Mpc(u32),
+    /// The groove extracted from the last MIDI file dropped onto the window.
+    Midi,
+}
+
+impl GrooveTemplate {
+    /// Every template, in the order they are shown by the UI.
+    pub const ALL: [Self; 8] = [
+        Self::Swing16,
+        Self::Swing8,
+        Self::Mpc(54),
+        Self::Mpc(58),
+        Self::Mpc(62),
+        Self::Mpc(66),
+        Self::Mpc(71),
+        Self::Midi,
+    ];
+
+    /// Returns the template at `idx` in [`GrooveTemplate::ALL`], or `Swing16`
+    /// if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or(Self::Swing16)
+    }
+
+    /// Returns the index of `self` in [`GrooveTemplate::ALL`].
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|t| *t == self).unwrap_or(0)
+    }
+
+    /// The template's short display name.
+    pub fn name(self) -> String {
+        match self {
+            Self::Swing16 => "1/16".to_string(),
+            Self::Swing8 => "1/8".to_string(),
+            Self::Mpc(percent) => format!("{percent}%"),
+            Self::Midi => "MIDI".to_string(),
+        }
+    }
+
+    /// Builds the template's groove, using `swing_amount` (`0.5` to `0.75`)
+    /// for the swing templates and `midi` for the MIDI template.
+    pub fn groove(self, swing_amount: f32, midi: Option<&Groove>) -> Groove {
+        match self {
+            Self::Swing16 => Groove::swing(16, swing_amount),
+            Self::Swing8 => Groove::swing(8, swing_amount),
+            Self::Mpc(percent) => Groove::swing(16, percent as f32 / 100.0),
+            Self::Midi => midi.cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_keep_the_first_step_and_the_order_of_steps() {
+        // the last step can't move before the (late) step before it
+        assert_eq!(
+            Groove::from_offsets(&[0.5, 2.0, -0.9]),
+            Groove { offsets: vec![0.0, MAX_OFFSET, 0.0] }
+        );
+        assert_eq!(Groove::from_offsets(&[]), Groove::straight());
+        assert_eq!(Groove::swing(2, 0.75), Groove::from_offsets(&[0.0, 0.5]));
+    }
+
+    #[test]
+    fn warps_time_so_each_step_lands_at_its_offset() {
+        // the second of two steps is half a step late, at 3/4 of the loop
+        let groove = Groove::from_offsets(&[0.0, 0.5]);
+
+        for (time, distance) in
+            [(0.0, 0.0), (0.375, 0.25), (0.75, 0.5), (0.875, 0.75), (1.0, 1.0)]
+        {
+            assert!(epsilon_eq(groove.warp(time), distance), "{time}");
+        }
+
+        let straight = Groove::straight();
+        assert_eq!(straight.warp(0.3), 0.3);
+    }
+
+    #[test]
+    fn warped_distance_never_goes_backwards() {
+        let groove = Groove::from_offsets(&[0.0, 0.9, -0.9, 0.4, -0.2]);
+        let mut last = 0.0;
+
+        for i in 0..=1000 {
+            let distance = groove.warp(i as f32 / 1000.0);
+
+            assert!(distance >= last, "{distance} < {last}");
+            last = distance;
+        }
+    }
+}
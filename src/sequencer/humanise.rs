@@ -0,0 +1,102 @@
+//! Module for humanising the timing and velocity of notes.
+
+use super::*;
+use crate::audio::voice::NoteEventData;
+
+/// How random timing offsets are distributed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JitterDistribution {
+    /// Every offset within the jitter range is equally likely.
+    Uniform,
+    /// Offsets cluster around the exact time, and reach the edge of the jitter
+    /// range at three standard deviations.
+    Gaussian,
+}
+
+impl JitterDistribution {
+    /// Every distribution, in the order they are shown by the UI.
+    pub const ALL: [Self; 2] = [Self::Uniform, Self::Gaussian];
+
+    /// Returns the distribution at `idx` in [`JitterDistribution::ALL`], or
+    /// `Uniform` if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or(Self::Uniform)
+    }
+
+    /// The distribution's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Uniform => "Flat",
+            Self::Gaussian => "Norm",
+        }
+    }
+}
+
+/// Settings for a [`Humaniser`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HumaniseSettings {
+    /// The largest distance of a note from its exact time, in milliseconds.
+    pub timing_ms: f32,
+    /// How timing offsets are distributed.
+    pub distribution: JitterDistribution,
+    /// The largest amount a note's velocity may be reduced by (`0.0` to
+    /// `1.0`).
+    pub velocity: f32,
+    /// The seed of the random offsets.
+    pub seed: u32,
+}
+
+/// Applies random, but reproducible, timing and velocity offsets to notes.
+///
+/// Notes can't be played before they are sent, so when timing jitter is
+/// enabled every note is delayed by the largest possible offset, and then
+/// moved earlier or later from there.
+#[derive(Clone, Debug)]
+pub struct Humaniser {
+    settings: HumaniseSettings,
+    rng: Rng,
+}
+
+impl Humaniser {
+    /// Creates a new `Humaniser`.
+    pub fn new(settings: HumaniseSettings) -> Self {
+        Self { settings, rng: Rng::new(settings.seed as u64) }
+    }
+
+    /// Updates the settings of the `Humaniser`, restarting its random offsets
+    /// if the seed has changed.
+    pub fn set_settings(&mut self, settings: HumaniseSettings) {
+        if settings.seed != self.settings.seed {
+            self.rng.reseed(settings.seed as u64);
+        }
+
+        self.settings = settings;
+    }
+
+    /// Restarts the random offsets from the seed, so they repeat exactly.
+    pub fn reset(&mut self) {
+        self.rng.reseed(self.settings.seed as u64);
+    }
+
+    /// Humanises the velocity of `data`, returning how long the note should
+    /// be delayed by in seconds.
+    pub fn humanise(&mut self, data: &mut NoteEventData) -> f32 {
+        let HumaniseSettings { timing_ms, distribution, velocity, .. } =
+            self.settings;
+
+        // both values are always drawn so that changing one setting doesn't
+        // change the other's random sequence
+        let jitter = match distribution {
+            JitterDistribution::Uniform => self.rng.range(-1.0, 1.0),
+            JitterDistribution::Gaussian => {
+                (self.rng.gaussian() / 3.0).clamp(-1.0, 1.0)
+            }
+        };
+        let reduction = self.rng.next_f32() * velocity.clamp(0.0, 1.0);
+
+        data.velocity *= 1.0 - reduction;
+
+        let max_offset = timing_ms.max(0.0) * 0.001;
+        jitter.mul_add(max_offset, max_offset)
+    }
+}
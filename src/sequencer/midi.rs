@@ -0,0 +1,244 @@
+//! Module for extracting grooves from standard MIDI files.
+
+use super::*;
+use std::path::Path;
+
+/// The number of grid steps per bar of an extracted groove (sixteenth notes,
+/// assuming 4/4).
+const GROOVE_STEPS: usize = 16;
+
+/// Reads the MIDI file at `path` and extracts its groove. See
+/// [`groove_from_midi()`].
+pub fn load_midi_groove(path: &Path) -> Result<Groove, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Failed to read MIDI file: {e}"))?;
+
+    groove_from_midi(&bytes)
+}
+
+/// Extracts a sixteenth-note groove from the bytes of a standard MIDI file.
+///
+/// Each note-on is matched to its nearest sixteenth note, and the offset of
+/// each sixteenth of the bar is the average distance of its notes from the
+/// grid, folding every bar of the file on top of each other. Sixteenths
+/// without any notes are left in place.
+pub fn groove_from_midi(bytes: &[u8]) -> Result<Groove, String> {
+    let (ticks_per_quarter, note_ticks) = parse_note_on_ticks(bytes)?;
+
+    if note_ticks.is_empty() {
+        return Err("The MIDI file doesn't contain any notes".to_string());
+    }
+
+    let ticks_per_step = ticks_per_quarter as f64 / 4.0;
+    let mut sums = [0.0; GROOVE_STEPS];
+    let mut counts = [0_u32; GROOVE_STEPS];
+
+    for tick in note_ticks {
+        let step = tick as f64 / ticks_per_step;
+        let nearest = step.round();
+        let idx = (nearest as u64 % GROOVE_STEPS as u64) as usize;
+
+        sums[idx] += step - nearest;
+        counts[idx] += 1;
+    }
+
+    let offsets: Vec<f32> = sums
+        .iter()
+        .zip(counts)
+        .map(|(&sum, count)| {
+            if count == 0 { 0.0 } else { (sum / count as f64) as f32 }
+        })
+        .collect();
+
+    Ok(Groove::from_offsets(&offsets))
+}
+
+/// Parses a standard MIDI file, returning its ticks per quarter note and the
+/// time (in ticks) of every note-on event across all of its tracks.
+fn parse_note_on_ticks(bytes: &[u8]) -> Result<(u16, Vec<u64>), String> {
+    let mut reader = Reader { bytes, pos: 0 };
+
+    if reader.take(4)? != b"MThd" {
+        return Err("Not a MIDI file".to_string());
+    }
+
+    let header_len = reader.u32()? as usize;
+    let header = reader.take(header_len)?;
+    if header.len() < 6 {
+        return Err("Invalid MIDI header".to_string());
+    }
+
+    let division = u16::from_be_bytes([header[4], header[5]]);
+    if division & 0x8000 != 0 || division == 0 {
+        return Err("SMPTE-timed MIDI files are not supported".to_string());
+    }
+
+    let mut ticks = Vec::new();
+
+    while reader.remaining() >= 8 {
+        let chunk_type = reader.take(4)?;
+        let chunk_len = reader.u32()? as usize;
+        let chunk = reader.take(chunk_len)?;
+
+        if chunk_type == b"MTrk" {
+            parse_track(chunk, &mut ticks)?;
+        }
+    }
+
+    Ok((division, ticks))
+}
+
+/// Parses the events of a single track chunk, appending the time of every
+/// note-on event to `ticks`.
+fn parse_track(bytes: &[u8], ticks: &mut Vec<u64>) -> Result<(), String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut time = 0_u64;
+    let mut running_status = None;
+
+    while reader.remaining() > 0 {
+        time += reader.var_len()? as u64;
+
+        let mut status = reader.u8()?;
+
+        // with running status, this byte is the first data byte instead
+        let is_running = status < 0x80;
+        if is_running {
+            status = running_status.ok_or("Invalid MIDI running status")?;
+        }
+
+        match status {
+            // meta event
+            0xFF => {
+                reader.u8()?;
+                let len = reader.var_len()? as usize;
+                reader.take(len)?;
+            }
+            // system exclusive events
+            0xF0 | 0xF7 => {
+                let len = reader.var_len()? as usize;
+                reader.take(len)?;
+            }
+            0x80..=0xEF => {
+                running_status = Some(status);
+
+                if !is_running {
+                    reader.u8()?;
+                }
+
+                // program change and channel pressure only have one data byte
+                let data_2 = match status & 0xF0 {
+                    0xC0 | 0xD0 => 0,
+                    _ => reader.u8()?,
+                };
+
+                // note-ons with a velocity of zero are note-offs
+                if status & 0xF0 == 0x90 && data_2 > 0 {
+                    ticks.push(time);
+                }
+            }
+            _ => return Err("Unsupported MIDI event".to_string()),
+        }
+    }
+
+    Ok(())
+}
+
+/// A simple big-endian byte reader.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.remaining() < len {
+            return Err("Unexpected end of MIDI file".to_string());
+        }
+
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Reads a variable-length quantity: 7 bits per byte, where the top bit
+    /// is set on every byte but the last.
+    fn var_len(&mut self) -> Result<u32, String> {
+        let mut value = 0_u32;
+
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7F) as u32;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("Invalid MIDI variable-length value".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a format 0 MIDI file with 96 ticks per quarter note and a
+    /// single track containing `events`.
+    fn midi_file(events: &[u8]) -> Vec<u8> {
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend([0, 0, 0, 6, 0, 0, 0, 1, 0, 96]);
+        bytes.extend(b"MTrk");
+        bytes.extend((events.len() as u32).to_be_bytes());
+        bytes.extend(events);
+        bytes
+    }
+
+    /// Note-ons on the first three sixteenths, with the second a quarter of
+    /// a step late. Every event after the first uses running status.
+    const EVENTS: [u8; 17] = [
+        0x00, 0x90, 0x3C, 0x64, // note-on at 0
+        0x1E, 0x3E, 0x64, // note-on at 30
+        0x12, 0x3C, 0x00, // note-off (a note-on without velocity) at 48
+        0x00, 0x40, 0x64, // note-on at 48
+        0x00, 0xFF, 0x2F, 0x00, // end of track
+    ];
+
+    #[test]
+    fn extracts_the_groove_of_notes_with_running_status() {
+        let mut offsets = [0.0; GROOVE_STEPS];
+        offsets[1] = 0.25;
+
+        assert_eq!(
+            groove_from_midi(&midi_file(&EVENTS)),
+            Ok(Groove::from_offsets(&offsets))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_malformed_files() {
+        let bytes = midi_file(&EVENTS);
+        for len in 0..bytes.len() {
+            assert!(groove_from_midi(&bytes[..len]).is_err(), "{len} bytes");
+        }
+
+        // running status before any status byte
+        assert!(groove_from_midi(&midi_file(&[0x00, 0x3C, 0x64])).is_err());
+        // a variable-length value which never ends
+        assert!(groove_from_midi(&midi_file(&[0xFF; 8])).is_err());
+        assert!(groove_from_midi(b"RIFF\0\0\0\x06\0\0\0\x01\0\x60").is_err());
+    }
+}
@@ -5,7 +5,9 @@ use nannou::text::{Align, Justify, Layout};
 use std::marker::PhantomData as PD;
 
 pub mod draw;
+pub mod panel;
 pub mod shape;
+pub mod text_input;
 pub mod text_slider;
 
 pub use draw::Drawable;
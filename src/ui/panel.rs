@@ -0,0 +1,268 @@
+//! Paged control panel, shown in the margins either side of the sequencer.
+
+use super::*;
+use crate::ui::text_slider::TextSlider;
+
+/// The number of slider slots in each column of a page.
+const ROWS_PER_COLUMN: usize = 11;
+/// The horizontal distance from the centre of the window to each column.
+const COLUMN_X: f32 = 370.0;
+/// The vertical position of the first row.
+const TOP_ROW_Y: f32 = 300.0;
+/// The vertical distance between rows.
+const ROW_SPACING: f32 = 60.0;
+/// The width of each slider.
+const SLOT_WIDTH: f32 = 56.0;
+/// The height of each slider.
+const SLOT_HEIGHT: f32 = 22.0;
+
+/// The font size used by sliders in the panel.
+pub const PANEL_FONT_SIZE: u32 = 14;
+
+/// Identifies each control in the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    EuclidHits,
+    EuclidSteps,
+    EuclidRotation,
+    LibraryShape,
+    SnapGrid,
+    SolveConvex,
+    SolveCentred,
+    ExportResolution,
+    SwingAmount,
+    GrooveTemplate,
+    PlayDirection,
+    GraphRule,
+    ShapeLoops,
+    ShapeBars,
+    ShapePhase,
+    PlayheadIndex,
+    PlayheadPhase,
+    PlayheadRatioNum,
+    PlayheadRatioDen,
+    PlayheadTranspose,
+    TriggerProbability,
+    TriggerCondition,
+    TriggerSeed,
+    RatchetCount,
+    RatchetSpacing,
+    RatchetRamp,
+    CurveKind,
+    TickGrid,
+    TickCount,
+    TickIndex,
+    TickPitch,
+    TickVelocity,
+    SensorRadius,
+    SensorNote,
+    CrossingsEnabled,
+    CrossingNote,
+    PathKind,
+    PathPoints,
+    PathWidth,
+    PathHeight,
+    PathStart,
+    PathSpan,
+    FreehandPoints,
+    FreehandDense,
+    RandomSeed,
+    RandomPoints,
+    RandomConvex,
+    RandomMinLength,
+    RandomSnap,
+    DriftMode,
+    DriftRate,
+    DriftDepth,
+    Engine,
+    BallCount,
+    BallSpeed,
+    PhysicsEnabled,
+    PhysicsStiffness,
+    PhysicsGravity,
+    PhysicsDamping,
+    MorphSlot,
+    MorphFrom,
+    MorphTo,
+    MorphAmount,
+    MorphBars,
+    HumaniseTiming,
+    HumaniseDistribution,
+    HumaniseVelocity,
+    HumaniseSeed,
+}
+
+/// Returns the rect of the `idx`th slot of a page. Slots fill the left
+/// column from top to bottom, and then the right column.
+pub fn slot_rect(idx: usize) -> Rect {
+    let column = idx / ROWS_PER_COLUMN;
+    let row = idx % ROWS_PER_COLUMN;
+
+    let x = if column == 0 { -COLUMN_X } else { COLUMN_X };
+    let y = (row as f32).mul_add(-ROW_SPACING, TOP_ROW_Y);
+
+    Rect::from_xy_wh(pt2(x, y), pt2(SLOT_WIDTH, SLOT_HEIGHT))
+}
+
+/// A titled page of sliders.
+pub struct ControlPage {
+    title: &'static str,
+    controls: Vec<(Control, TextSlider)>,
+}
+
+impl ControlPage {
+    /// Creates a new, empty `ControlPage`.
+    pub fn new(title: &'static str) -> Self {
+        Self { title, controls: Vec::new() }
+    }
+
+    /// Adds a slider to the page. `build` is passed the rect of the next free
+    /// slot, which should be used as the slider's rect.
+    pub fn with_slider<F>(mut self, control: Control, build: F) -> Self
+    where
+        F: FnOnce(Rect) -> TextSlider,
+    {
+        debug_assert!(self.controls.len() < ROWS_PER_COLUMN * 2);
+
+        let slider = build(slot_rect(self.controls.len()))
+            .with_font_size(PANEL_FONT_SIZE);
+        self.controls.push((control, slider));
+
+        self
+    }
+}
+
+/// A set of pages of sliders, of which one is shown at a time.
+///
+/// Press tab to cycle through the pages.
+pub struct ControlPanel {
+    pages: Vec<ControlPage>,
+    current: usize,
+
+    redraw_requested: bool,
+    needs_redraw: bool,
+
+    rect: Rect,
+    title_rect: Rect,
+}
+
+impl ControlPanel {
+    /// Creates a new `ControlPanel` with no pages.
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: 0,
+
+            redraw_requested: true,
+            needs_redraw: true,
+
+            rect: Rect::from_wh(pt2(
+                COLUMN_X.mul_add(2.0, SLOT_WIDTH),
+                800.0,
+            )),
+            title_rect: Rect::from_xy_wh(
+                pt2(-277.0, 380.0),
+                pt2(235.0, 30.0),
+            ),
+        }
+    }
+
+    /// Adds a page to the panel.
+    pub fn with_page(mut self, page: ControlPage) -> Self {
+        self.pages.push(page);
+        self
+    }
+
+    /// Shows the next page, wrapping back around to the first.
+    pub fn next_page(&mut self) {
+        if self.pages.is_empty() {
+            return;
+        }
+
+        self.current = (self.current + 1) % self.pages.len();
+        self.redraw_requested = true;
+    }
+
+    /// Returns a mutable reference to the slider for `control`, regardless of
+    /// which page it is on.
+    pub fn slider_mut(
+        &mut self,
+        control: Control,
+    ) -> Option<&mut TextSlider> {
+        self.pages
+            .iter_mut()
+            .flat_map(|page| page.controls.iter_mut())
+            .find(|(c, _)| *c == control)
+            .map(|(_, slider)| slider)
+    }
+
+    fn current_page(&self) -> Option<&ControlPage> {
+        self.pages.get(self.current)
+    }
+}
+
+impl Drawable for ControlPanel {
+    fn update(&mut self, input_data: &InputData) {
+        self.needs_redraw = self.redraw_requested;
+        self.redraw_requested = false;
+
+        if let Some(page) = self.pages.get_mut(self.current) {
+            for (_, slider) in &mut page.controls {
+                slider.update(input_data);
+            }
+        }
+    }
+
+    fn draw(&self, draw: &Draw, frame: &Frame) {
+        if frame.nth() == 0 || self.needs_redraw {
+            self.force_redraw(draw, frame);
+            return;
+        }
+
+        if let Some(page) = self.current_page() {
+            for (_, slider) in &page.controls {
+                slider.draw(draw, frame);
+            }
+        }
+    }
+
+    fn force_redraw(&self, draw: &Draw, frame: &Frame) {
+        // clear whatever the previous page left behind
+        for x in [-COLUMN_X, COLUMN_X] {
+            draw.rect()
+                .x_y(x, 0.0)
+                .w_h(SLOT_WIDTH + 2.0, 800.0)
+                .color(WHITE);
+        }
+
+        draw.rect()
+            .xy(self.title_rect.xy())
+            .wh(self.title_rect.wh())
+            .color(WHITE);
+
+        let Some(page) = self.current_page() else {
+            return;
+        };
+
+        draw.text(&format!(
+            "{} ({}/{}, tab)",
+            page.title,
+            self.current + 1,
+            self.pages.len()
+        ))
+        .xy(self.title_rect.xy())
+        .wh(self.title_rect.wh())
+        .color(BLACK)
+        .font_size(16)
+        .left_justify();
+
+        for (_, slider) in &page.controls {
+            slider.force_redraw(draw, frame);
+            slider.redraw_label(draw);
+        }
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+}
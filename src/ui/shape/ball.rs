@@ -0,0 +1,273 @@
+//! Module for balls, which bounce around inside a shape and play a note each
+//! time they hit one of its segments.
+
+use super::*;
+
+/// The largest number of balls in a shape.
+pub const MAX_BALLS: usize = 8;
+/// The radius each ball is drawn with.
+const BALL_RADIUS: f32 = 7.0;
+/// The most times one ball can bounce in one update.
+const MAX_BOUNCES: usize = 8;
+/// The shortest distance a ball must travel before it can hit a segment,
+/// so it doesn't hit the segment it just bounced off again.
+const MIN_TRAVEL: f32 = 1e-3;
+/// The angle between the directions of consecutive balls, which spreads them
+/// evenly whatever their number.
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// What plays the notes of a shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Engine {
+    /// Playheads travel around the shape, playing each vertex they reach.
+    #[default]
+    Playheads,
+    /// Balls bounce around inside the shape, playing each segment they hit.
+    Balls,
+    /// A clock hand turns around the middle of the shape, playing each
+    /// vertex it points at.
+    Radial,
+}
+
+impl Engine {
+    /// Every engine, in the order they are shown by the UI.
+    pub const ALL: [Self; 3] = [Self::Playheads, Self::Balls, Self::Radial];
+
+    /// Returns the engine at `idx` in [`Engine::ALL`], or `Playheads` if
+    /// `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The engine's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Playheads => "Heads",
+            Self::Balls => "Balls",
+            Self::Radial => "Radial",
+        }
+    }
+}
+
+/// Settings for what plays the notes of a shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BallSettings {
+    pub engine: Engine,
+    /// The number of balls.
+    pub count: usize,
+    /// The distance each ball travels in one loop of the shape, relative to
+    /// the shape's perimeter.
+    pub speed: f32,
+}
+
+impl Default for BallSettings {
+    fn default() -> Self {
+        Self { engine: Engine::Playheads, count: 1, speed: 1.0 }
+    }
+}
+
+/// A ball moving in a straight line until it hits a segment.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Ball {
+    pos: Vec2,
+    /// The direction the ball is moving in, with a length of `1.0`.
+    dir: Vec2,
+}
+
+/// Where a ball moving in a straight line first hits a segment.
+struct Hit {
+    segment: usize,
+    /// How far along the ball's path the hit is, from `0.0` to `1.0`.
+    interp: f32,
+    /// The direction of the segment where it is hit.
+    tangent: Vec2,
+}
+
+impl Sequence {
+    /// Sets what plays the notes of the shape. The balls and the radial
+    /// clock start again from the middle of the shape if the engine changes,
+    /// and the balls also do if their number changes.
+    pub fn set_balls(&mut self, settings: BallSettings) {
+        let engine_changed = settings.engine != self.ball_settings.engine;
+        let restart =
+            engine_changed || settings.count != self.ball_settings.count;
+        self.ball_settings = settings;
+
+        if restart {
+            self.reset_balls();
+        }
+        if engine_changed {
+            self.reset_radial();
+        }
+    }
+
+    /// Places every ball in the middle of the shape, each heading in a
+    /// different direction.
+    pub(super) fn reset_balls(&mut self) {
+        let centre = self.interior_point();
+        let count = self.ball_settings.count.min(MAX_BALLS);
+
+        self.balls = (0..count)
+            .map(|i| {
+                let angle = (i as f32).mul_add(GOLDEN_ANGLE, PI * 0.3);
+                Ball { pos: centre, dir: vec2(angle.cos(), angle.sin()) }
+            })
+            .collect();
+        self.ball_clock = self.clock;
+    }
+
+    /// Returns the straight lines between the sample points of each segment
+    /// (see [`Segment::sample_points()`]), with the index of their segment.
+    fn outline_pieces(&self) -> impl Iterator<Item = (usize, Vec2, Vec2)> + '_ {
+        self.segments[..self.num_nodes]
+            .iter()
+            .enumerate()
+            .flat_map(|(i, segment)| {
+                let starts = segment.sample_points().map(|(pos, _)| pos);
+                let ends = segment.sample_points().skip(1).map(|(pos, _)| pos);
+                starts.zip(ends).map(move |(start, end)| (i, start, end))
+            })
+    }
+
+    /// Returns whether `point` is inside the shape, by the even-odd rule.
+    /// The gap of an open path counts as part of the outline.
+    fn encloses(&self, point: Vec2) -> bool {
+        // whether the piece crosses the horizontal line to the right of it
+        let crosses = |&(_, start, end): &(usize, Vec2, Vec2)| {
+            if (start.y > point.y) == (end.y > point.y) {
+                return false;
+            }
+
+            let interp = (point.y - start.y) / (end.y - start.y);
+            point.x < lerp(start.x, end.x, interp)
+        };
+
+        self.outline_pieces().filter(crosses).count() % 2 == 1
+    }
+
+    /// Returns a point inside the shape: its middle if that is inside it, or
+    /// otherwise the middle of the widest part of the shape level with it.
+    fn interior_point(&self) -> Vec2 {
+        let centre = self.centre();
+        if self.encloses(centre) {
+            return centre;
+        }
+
+        // where the horizontal line through the middle crosses the outline
+        let mut crossings: Vec<f32> = self
+            .outline_pieces()
+            .filter(|&(_, start, end)| {
+                (start.y > centre.y) != (end.y > centre.y)
+            })
+            .map(|(_, start, end)| {
+                let interp = (centre.y - start.y) / (end.y - start.y);
+                lerp(start.x, end.x, interp)
+            })
+            .collect();
+        crossings.sort_unstable_by(f32::total_cmp);
+
+        crossings
+            .chunks_exact(2)
+            .max_by(|a, b| (a[1] - a[0]).total_cmp(&(b[1] - b[0])))
+            .map_or(centre, |span| vec2((span[0] + span[1]) * 0.5, centre.y))
+    }
+
+    /// Returns where a ball moving in a straight line from `from` to `to`
+    /// first hits a segment, if it does.
+    fn first_hit(&self, from: Vec2, to: Vec2) -> Option<Hit> {
+        let min_interp = MIN_TRAVEL / from.distance(to).max(f32::EPSILON);
+        let mut first: Option<Hit> = None;
+
+        for (i, start, end) in self.outline_pieces() {
+            if self.segments[i].curve.is_gap {
+                continue;
+            }
+
+            let Some((interp, _)) = intersect(from, to, start, end) else {
+                continue;
+            };
+
+            if interp > min_interp
+                && first.as_ref().is_none_or(|hit| interp < hit.interp)
+            {
+                let tangent = (end - start).normalize_or_zero();
+                first = Some(Hit { segment: i, interp, tangent });
+            }
+        }
+
+        first
+    }
+
+    /// Moves each ball by the distance it travels in the time the master
+    /// clock has moved on since the last update, playing the note of the
+    /// vertex at the start of each segment it hits.
+    pub(super) fn update_balls(&mut self) {
+        // the clock can jump backwards, such as when the timing changes
+        let loops = (self.clock - self.ball_clock).clamp(0.0, 1.0) as f32;
+        self.ball_clock = self.clock;
+
+        if self.ball_settings.engine != Engine::Balls {
+            return;
+        }
+
+        let distance = loops * self.ball_settings.speed * self.length;
+        let mut balls = std::mem::take(&mut self.balls);
+        let mut hits = Vec::new();
+
+        for ball in &mut balls {
+            let mut remaining = distance;
+
+            for _ in 0..MAX_BOUNCES {
+                let to = ball.pos + ball.dir * remaining;
+
+                let Some(hit) = self.first_hit(ball.pos, to) else {
+                    ball.pos = to;
+                    break;
+                };
+
+                ball.pos = ball.pos.lerp(to, hit.interp);
+                remaining *= 1.0 - hit.interp;
+
+                // reflect the ball's direction about the segment
+                let normal = vec2(-hit.tangent.y, hit.tangent.x);
+                ball.dir -= normal * (2.0 * ball.dir.dot(normal));
+
+                hits.push(hit.segment);
+            }
+
+            // a ball can escape if a vertex is dragged over it, or through
+            // the gap of an open path. It is checked just ahead of where it
+            // is, as it may have stopped on the segment it bounced off
+            if !self.encloses(ball.pos + ball.dir * MIN_TRAVEL) {
+                ball.pos = self.interior_point();
+            }
+        }
+
+        self.balls = balls;
+
+        for segment in hits {
+            let node = &mut self.nodes[segment];
+            node.tap();
+
+            self.note_data_sender
+                .send(node.note_data())
+                .expect("failed to send note data");
+        }
+    }
+
+    /// Draws each ball, if they play the shape.
+    pub(super) fn draw_balls(&self, draw: &Draw) {
+        if self.ball_settings.engine != Engine::Balls {
+            return;
+        }
+
+        for ball in &self.balls {
+            draw.ellipse()
+                .xy(ball.pos)
+                .radius(BALL_RADIUS)
+                .color(WHITE)
+                .stroke_color(BLACK)
+                .stroke_weight(2.0);
+        }
+    }
+}
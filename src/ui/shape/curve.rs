@@ -0,0 +1,363 @@
+//! Module for curved segments, which bend into Bézier curves.
+
+use super::*;
+
+/// The number of pieces a curved segment is split into to measure its length.
+pub(super) const CURVE_RESOLUTION: usize = 32;
+/// The distance within which a handle can be grabbed by the mouse.
+const HANDLE_RADIUS: f32 = 6.0;
+
+/// The kind of curve a segment follows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CurveKind {
+    /// A straight line.
+    #[default]
+    Straight,
+    /// A quadratic Bézier curve, bent by one handle.
+    Quadratic,
+    /// A cubic Bézier curve, bent by a handle at each end.
+    Cubic,
+}
+
+impl CurveKind {
+    /// Every kind of curve, in the order they are shown by the UI.
+    pub const ALL: [Self; 3] = [Self::Straight, Self::Quadratic, Self::Cubic];
+
+    /// Returns the kind of curve at `idx` in [`CurveKind::ALL`], or
+    /// `Straight` if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Returns the index of the kind of curve in [`CurveKind::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The kind of curve's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Straight => "Line",
+            Self::Quadratic => "Quad",
+            Self::Cubic => "Cubic",
+        }
+    }
+
+    /// The number of handles which bend the curve.
+    fn num_handles(self) -> usize {
+        match self {
+            Self::Straight => 0,
+            Self::Quadratic => 1,
+            Self::Cubic => 2,
+        }
+    }
+}
+
+/// The shape of a segment between its two vertices.
+///
+/// Handles are stored relative to the vertices, so they follow the vertices
+/// as they are dragged: a quadratic curve's handle is relative to the middle
+/// of the segment, and a cubic curve's handles are relative to the start and
+/// end respectively.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(super) struct Curve {
+    pub kind: CurveKind,
+    pub handles: [Vec2; 2],
+    /// The part of an ellipse the segment follows instead, if any.
+    pub arc: Option<EllipseArc>,
+    /// Whether the segment is the gap which closes an open path, which the
+    /// playhead jumps across instantly.
+    pub is_gap: bool,
+}
+
+impl Segment {
+    /// Returns whether the segment is a straight line.
+    pub(super) fn is_straight(&self) -> bool {
+        self.curve.kind == CurveKind::Straight && self.curve.arc.is_none()
+    }
+
+    /// Returns the positions of the segment's handles.
+    fn handle_positions(&self) -> [Vec2; 2] {
+        let [h0, h1] = self.curve.handles;
+
+        match self.curve.kind {
+            CurveKind::Quadratic => {
+                let mid = self.start.lerp(self.end, 0.5);
+                [mid + h0, mid + h0]
+            }
+            _ => [self.start + h0, self.end + h1],
+        }
+    }
+
+    /// Returns the four control points of the segment as a cubic Bézier
+    /// curve.
+    fn control_points(&self) -> [Vec2; 4] {
+        let (start, end) = (self.start, self.end);
+        let [h0, h1] = self.handle_positions();
+
+        match self.curve.kind {
+            CurveKind::Straight => [
+                start,
+                start.lerp(end, 1.0 / 3.0),
+                start.lerp(end, 2.0 / 3.0),
+                end,
+            ],
+            // a quadratic curve is raised to the cubic curve with the same
+            // shape
+            CurveKind::Quadratic => [
+                start,
+                start.lerp(h0, 2.0 / 3.0),
+                end.lerp(h0, 2.0 / 3.0),
+                end,
+            ],
+            CurveKind::Cubic => [start, h0, h1, end],
+        }
+    }
+
+    /// Returns the point on the segment's curve at `t` (`0.0` to `1.0`), which
+    /// is not evenly spaced along the curve.
+    fn curve_point(&self, t: f32) -> Vec2 {
+        if let Some(arc) = self.curve.arc {
+            return arc.point(t);
+        }
+
+        let [p0, p1, p2, p3] = self.control_points();
+        let u = 1.0 - t;
+
+        p0 * (u * u * u)
+            + p1 * (3.0 * u * u * t)
+            + p2 * (3.0 * u * t * t)
+            + p3 * (t * t * t)
+    }
+
+    /// Works out the length of the segment, and how far along its curve each
+    /// of its sample points is.
+    pub fn calculate_distance(&mut self) {
+        if self.curve.is_gap {
+            self.length = 0.0;
+            return;
+        }
+
+        if self.is_straight() {
+            self.length = self.start.distance(self.end);
+            return;
+        }
+
+        let mut length = 0.0;
+        let mut last = self.start;
+
+        for i in 1..=CURVE_RESOLUTION {
+            let point = self.curve_point(i as f32 / CURVE_RESOLUTION as f32);
+            length += last.distance(point);
+            last = point;
+
+            self.arc_lengths[i] = length;
+        }
+
+        self.arc_lengths[0] = 0.0;
+        self.length = length;
+    }
+
+    /// Returns the point `interp` of the way along the segment (`0.0` to
+    /// `1.0`), measured along its curve.
+    pub fn point_at(&self, interp: f32) -> Vec2 {
+        if self.is_straight() {
+            return self.start.lerp(self.end, interp);
+        }
+
+        self.curve_point(self.curve_param(interp))
+    }
+
+    /// Returns the direction of the segment `interp` of the way along it.
+    pub fn direction_at(&self, interp: f32) -> Vec2 {
+        if self.is_straight() {
+            return (self.end - self.start).normalize_or_zero();
+        }
+
+        let t = self.curve_param(interp);
+        let step = 0.5 / CURVE_RESOLUTION as f32;
+        let before = self.curve_point((t - step).max(0.0));
+        let after = self.curve_point((t + step).min(1.0));
+
+        (after - before).normalize_or_zero()
+    }
+
+    /// Returns the curve parameter of the point `interp` of the way along the
+    /// segment.
+    fn curve_param(&self, interp: f32) -> f32 {
+        let target = interp.clamp(0.0, 1.0) * self.length;
+        let idx = self.arc_lengths[1..=CURVE_RESOLUTION]
+            .partition_point(|&len| len < target)
+            .min(CURVE_RESOLUTION - 1);
+
+        let (from, to) = (self.arc_lengths[idx], self.arc_lengths[idx + 1]);
+        let local = if to > from { (target - from) / (to - from) } else { 0.0 };
+
+        (idx as f32 + local) / CURVE_RESOLUTION as f32
+    }
+
+    /// Returns the sample points along the segment, with how far along it
+    /// (`0.0` to `1.0`) each one is. Straight segments only have their ends.
+    pub fn sample_points(&self) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        let num = if self.is_straight() {
+            1
+        }
+        else {
+            CURVE_RESOLUTION
+        };
+        let length = self.length.max(f32::EPSILON);
+
+        (0..=num).map(move |i| {
+            if num == 1 {
+                (if i == 0 { self.start } else { self.end }, i as f32)
+            }
+            else {
+                let point = self.curve_point(i as f32 / num as f32);
+                (point, self.arc_lengths[i] / length)
+            }
+        })
+    }
+
+    /// Returns points along the part of the segment between `from` and `to`
+    /// (as interpolation values), close enough together that the path between
+    /// them can be treated as straight.
+    pub fn points_between(
+        &self,
+        from: f32,
+        to: f32,
+    ) -> impl Iterator<Item = Vec2> + '_ {
+        let num = if self.is_straight() {
+            1
+        }
+        else {
+            ((to - from).abs() * CURVE_RESOLUTION as f32).ceil().max(1.0)
+                as usize
+        };
+
+        (0..=num).map(move |i| {
+            self.point_at(lerp(from, to, i as f32 / num as f32))
+        })
+    }
+
+    /// Sets the kind of curve the segment follows, placing its handles so it
+    /// starts out straight. Any arc it follows, and whether it is a gap, are
+    /// kept.
+    fn set_curve_kind(&mut self, kind: CurveKind) {
+        let third = (self.end - self.start) / 3.0;
+
+        self.curve = Curve {
+            kind,
+            handles: match kind {
+                CurveKind::Cubic => [third, -third],
+                _ => [Vec2::ZERO; 2],
+            },
+            ..self.curve
+        };
+        self.calculate_distance();
+    }
+}
+
+impl Sequence {
+    /// Returns the kind of curve the segment after the `idx`th node follows,
+    /// if it exists.
+    pub fn curve_kind(&self, idx: usize) -> Option<CurveKind> {
+        self.segments[..self.num_nodes].get(idx).map(|s| s.curve.kind)
+    }
+
+    /// Sets the kind of curve the segment after the `idx`th node follows, if
+    /// it exists. The segment starts out straight, and is bent by dragging
+    /// its handles.
+    pub fn set_curve_kind(&mut self, idx: usize, kind: CurveKind) {
+        let Some(segment) = self.segments[..self.num_nodes].get_mut(idx)
+        else {
+            return;
+        };
+
+        if segment.curve.kind != kind {
+            segment.set_curve_kind(kind);
+            self.update_segments();
+            self.update_length();
+        }
+    }
+
+    /// Makes every segment straight.
+    pub(super) fn straighten_segments(&mut self) {
+        for segment in &mut self.segments {
+            segment.curve = Curve::default();
+        }
+    }
+
+    /// Lets the handles of curved segments be dragged with the mouse,
+    /// returning whether a handle is being dragged.
+    pub(super) fn update_handles(&mut self, input_data: &InputData) -> bool {
+        let mp = input_data.mouse_pos;
+
+        if !input_data.is_left_clicked {
+            self.dragged_handle = None;
+        }
+        else if input_data.left_button_just_clicked() {
+            self.dragged_handle =
+                self.segments[..self.num_nodes].iter().enumerate().find_map(
+                    |(i, segment)| {
+                        segment
+                            .handle_positions()
+                            .iter()
+                            .take(segment.curve.kind.num_handles())
+                            .position(|h| h.distance(mp) <= HANDLE_RADIUS)
+                            .map(|handle| (i, handle))
+                    },
+                );
+        }
+
+        let Some((idx, handle)) = self.dragged_handle else {
+            return false;
+        };
+
+        let mp = mp.clamp(self.rect.bottom_left(), self.rect.top_right());
+        let segment = &mut self.segments[idx];
+        let anchor = match (segment.curve.kind, handle) {
+            (CurveKind::Quadratic, _) => segment.start.lerp(segment.end, 0.5),
+            (_, 0) => segment.start,
+            _ => segment.end,
+        };
+        segment.curve.handles[handle] = mp - anchor;
+
+        self.update_segments();
+        self.update_length();
+
+        true
+    }
+
+    /// Draws the handles of each curved segment, with a line to the vertex
+    /// (or point) they are relative to.
+    pub(super) fn draw_handles(&self, draw: &Draw) {
+        for segment in self.segments.iter().take(self.num_nodes) {
+            let kind = segment.curve.kind;
+            let anchors = match kind {
+                CurveKind::Quadratic => {
+                    [segment.start.lerp(segment.end, 0.5); 2]
+                }
+                _ => [segment.start, segment.end],
+            };
+
+            for (handle, anchor) in segment
+                .handle_positions()
+                .into_iter()
+                .zip(anchors)
+                .take(kind.num_handles())
+            {
+                draw.line()
+                    .start(anchor)
+                    .end(handle)
+                    .color(GREY)
+                    .weight(1.0);
+                draw.rect()
+                    .xy(handle)
+                    .w_h(HANDLE_RADIUS * 1.5, HANDLE_RADIUS * 1.5)
+                    .color(WHITE)
+                    .stroke_color(GREY)
+                    .stroke_weight(1.5);
+            }
+        }
+    }
+}
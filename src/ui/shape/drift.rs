@@ -0,0 +1,152 @@
+//! Module for drift, which makes the vertices of a shape slowly wander.
+
+use super::*;
+
+/// Used to spread noise curves apart.
+const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+
+/// How the vertices of a shape wander over time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DriftMode {
+    /// The vertices stay where they are.
+    #[default]
+    Off,
+    /// Each vertex takes a random walk.
+    Walk,
+    /// Each vertex follows smooth noise.
+    Noise,
+}
+
+impl DriftMode {
+    /// Every mode, in the order they are shown by the UI.
+    pub const ALL: [Self; 3] = [Self::Off, Self::Walk, Self::Noise];
+
+    /// Returns the mode at `idx` in [`DriftMode::ALL`], or `Off` if `idx` is
+    /// out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The mode's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Walk => "Walk",
+            Self::Noise => "Noise",
+        }
+    }
+}
+
+/// Settings for how the vertices of a shape wander.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DriftSettings {
+    pub mode: DriftMode,
+    /// How quickly the vertices wander, in cycles per second.
+    pub rate: f32,
+    /// The furthest each vertex may wander from where it was placed, in
+    /// pixels.
+    pub depth: f32,
+}
+
+/// Returns smooth gradient noise at `x`, between about `-1.0` and `1.0`.
+/// Each `seed` gives a different, unrelated curve.
+///
+/// `x` is an `f64` so the noise stays smooth after the app has run for days.
+fn noise(x: f64, seed: u32) -> f32 {
+    // gradient noise is always zero at whole numbers, so each curve is
+    // shifted by a different amount to stop them all passing through zero
+    // together
+    let x = (seed as f64).mul_add(GOLDEN_RATIO, x);
+    let cell = x.floor();
+    let local = (x - cell) as f32;
+
+    let gradient = |cell: f64| {
+        let mut rng = Rng::new(((seed as u64) << 32) ^ cell as i64 as u64);
+        rng.range(-1.0, 1.0)
+    };
+
+    // smootherstep, so the noise never changes direction suddenly
+    let fade = local.powi(3) * local.mul_add(local.mul_add(6.0, -15.0), 10.0);
+    let from = gradient(cell) * local;
+    let to = gradient(cell + 1.0) * (local - 1.0);
+
+    // the largest value of 1D gradient noise is 0.5
+    lerp(from, to, fade) * 2.0
+}
+
+impl Sequence {
+    /// Sets how the vertices wander over time. Turning drift off leaves the
+    /// vertices where they are.
+    pub fn set_drift(&mut self, settings: DriftSettings) {
+        self.drift = settings;
+
+        if settings.mode == DriftMode::Off {
+            self.reset_drift();
+        }
+    }
+
+    /// Sets whether the vertices are held where they are. Drift carries on
+    /// from the same point once they are released.
+    pub fn set_drift_frozen(&mut self, frozen: bool) {
+        self.drift_frozen = frozen;
+    }
+
+    /// Returns whether the vertices are held where they are.
+    pub fn is_drift_frozen(&self) -> bool {
+        self.drift_frozen
+    }
+
+    /// Makes the vertices' current positions the ones they wander around.
+    pub(super) fn reset_drift(&mut self) {
+        self.drift_offsets.fill(Vec2::ZERO);
+        self.drift_phase = 0.0;
+    }
+
+    /// Moves each vertex (other than one being dragged) along its random walk
+    /// or noise curve, keeping it within the shape's rect. Noise fades in over
+    /// its first cycle, so the vertices don't jump when it starts.
+    pub(super) fn update_drift(&mut self, input_data: &InputData) {
+        let DriftSettings { mode, rate, depth } = self.drift;
+        if mode == DriftMode::Off || self.drift_frozen {
+            return;
+        }
+
+        let dt = input_data.delta_time;
+        self.drift_phase += (dt * rate) as f64;
+        let phase = self.drift_phase;
+        let noise_depth = depth * phase.min(1.0) as f32;
+
+        for i in 0..self.num_nodes {
+            if self.clicked_idx == Some(i) {
+                continue;
+            }
+
+            let offset = self.drift_offsets[i];
+            let anchor = self.nodes[i].pos - offset;
+
+            let offset = match mode {
+                DriftMode::Walk => {
+                    let rng = &mut self.drift_rng;
+                    let step = vec2(rng.range(-1.0, 1.0), rng.range(-1.0, 1.0))
+                        * depth
+                        * (rate * dt).sqrt();
+
+                    (offset + step).clamp_length_max(depth)
+                }
+                _ => {
+                    let seed = i as u32 * 2;
+                    vec2(noise(phase, seed), noise(phase, seed + 1))
+                        * noise_depth
+                }
+            };
+
+            let pos = (anchor + offset)
+                .clamp(self.rect.bottom_left(), self.rect.top_right());
+            self.drift_offsets[i] = pos - anchor;
+            self.move_node(i, pos);
+        }
+
+        self.update_segments();
+        self.update_length();
+    }
+}
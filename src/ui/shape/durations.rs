@@ -0,0 +1,365 @@
+//! Module for solving a shape from a typed rhythm.
+
+use super::*;
+
+/// The number of iterations used to relax a non-convex shape.
+const RELAX_ITERATIONS: usize = 500;
+
+/// Options for [`Sequence::set_durations()`].
+#[derive(Clone, Copy, Debug)]
+pub struct SolveOptions {
+    /// Whether to solve a convex shape (a polygon inscribed in a circle), or
+    /// to bend the current shape until it has the right proportions.
+    pub convex: bool,
+    /// Whether to move the shape to the centre of the sequencer.
+    pub centred: bool,
+}
+
+/// Parses a typed rhythm into a list of durations.
+///
+/// Each duration is either a number of sixteenth notes (`4 2 2 3 3 2`), or a
+/// note value (`q e e q.`): `w`, `h`, `q`, `e`, `s` and `t` represent whole,
+/// half, quarter, eighth, sixteenth and thirty-second notes. Note values may be
+/// followed by `3` to make them triplets, and by any number of dots.
+///
+/// Durations may be separated by whitespace or commas.
+pub fn parse_durations(text: &str) -> Result<Vec<f32>, String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse::<f32>()
+                .ok()
+                .filter(|d| d.is_finite() && *d > 0.0)
+                .or_else(|| parse_note_value(token))
+                .ok_or_else(|| format!("Unknown duration \"{token}\""))
+        })
+        .collect()
+}
+
+/// Parses a note value such as `q`, `e3` or `h.` into sixteenth notes.
+fn parse_note_value(token: &str) -> Option<f32> {
+    let mut chars = token.chars().peekable();
+
+    let mut value = match chars.next()? {
+        'w' => 16.0,
+        'h' => 8.0,
+        'q' => 4.0,
+        'e' => 2.0,
+        's' => 1.0,
+        't' => 0.5,
+        _ => return None,
+    };
+
+    if chars.next_if_eq(&'3').is_some() {
+        value *= 2.0 / 3.0;
+    }
+
+    // each dot adds half of the previous addition
+    let mut addition = value * 0.5;
+    for ch in chars {
+        if ch != '.' {
+            return None;
+        }
+
+        value += addition;
+        addition *= 0.5;
+    }
+
+    Some(value)
+}
+
+impl Sequence {
+    /// Lays the sequence out so that the time between each vertex follows
+    /// `durations`, which only need to be correct relative to each other.
+    ///
+    /// If one duration is at least as long as all of the others combined, no
+    /// such polygon exists, so it is split in half by a rest until one does.
+    pub fn set_durations(
+        &mut self,
+        durations: &[f32],
+        options: SolveOptions,
+    ) -> Result<(), String> {
+        if durations.len() < MIN_NUM_VERTICES - 1 {
+            return Err(format!(
+                "At least {} durations are needed",
+                MIN_NUM_VERTICES - 1
+            ));
+        }
+
+        let (lengths, rests) = split_infeasible(durations)?;
+
+        // the shape keeps its perimeter, as the durations are only relative
+        let scale = self.length / lengths.iter().sum::<f32>();
+        let lengths: Vec<f32> = lengths.iter().map(|l| l * scale).collect();
+
+        let mut vertices = if options.convex {
+            cyclic_polygon(&lengths)
+        }
+        else {
+            self.relaxed_polygon(&lengths)
+        };
+
+        self.fit_to_rect(&mut vertices, options.centred);
+        self.set_vertices(&vertices, &rests);
+
+        Ok(())
+    }
+
+    /// Bends the current shape so its segments follow `lengths`, which should
+    /// add up to its perimeter.
+    ///
+    /// The new vertices start at the points along the current shape at the
+    /// right proportions of its perimeter, and are then pulled towards their
+    /// target distances from each other.
+    fn relaxed_polygon(&self, lengths: &[f32]) -> Vec<Vec2> {
+        let mut elapsed = 0.0;
+        let mut vertices: Vec<Vec2> = lengths
+            .iter()
+            .map(|target| {
+                let pos = self.point_at_length(elapsed);
+                elapsed += target;
+                pos
+            })
+            .collect();
+
+        let num = vertices.len();
+
+        for _ in 0..RELAX_ITERATIONS {
+            for (i, &target) in lengths.iter().enumerate() {
+                let j = (i + 1) % num;
+                let delta = vertices[j] - vertices[i];
+                let dist = delta.length();
+
+                if dist <= f32::EPSILON {
+                    continue;
+                }
+
+                let correction = delta * (0.5 * (dist - target) / dist);
+                vertices[i] += correction;
+                vertices[j] -= correction;
+            }
+        }
+
+        vertices
+    }
+
+    /// Scales `vertices` down (about the centre of the sequencer) if they
+    /// don't fit within it, optionally moving them to its centre first.
+    fn fit_to_rect(&self, vertices: &mut [Vec2], centred: bool) {
+        let bounds = Rect::from_corners(
+            vertices.iter().fold(Vec2::splat(f32::MAX), |a, &b| a.min(b)),
+            vertices.iter().fold(Vec2::splat(f32::MIN), |a, &b| a.max(b)),
+        );
+
+        if centred {
+            let offset = self.rect.xy() - bounds.xy();
+            vertices.iter_mut().for_each(|v| *v += offset);
+        }
+
+        let (w, h) = self.rect.w_h();
+        let max_extent = vertices.iter().fold(Vec2::ZERO, |a, &v| {
+            a.max((v - self.rect.xy()).abs())
+        });
+        let scale = f32::min(w * 0.5 / max_extent.x, h * 0.5 / max_extent.y);
+
+        if scale < 1.0 {
+            let centre = self.rect.xy();
+            vertices
+                .iter_mut()
+                .for_each(|v| *v = (*v - centre) * scale + centre);
+        }
+    }
+}
+
+/// Splits the longest of `durations` in half until every duration is shorter
+/// than all of the others combined, as required for a polygon to exist.
+///
+/// Returns the lengths of each side, and whether the vertex at the start of
+/// each side is a rest.
+fn split_infeasible(
+    durations: &[f32],
+) -> Result<(Vec<f32>, Vec<bool>), String> {
+    let mut sides: Vec<(f32, bool)> =
+        durations.iter().map(|&d| (d, false)).collect();
+    let total: f32 = durations.iter().sum();
+
+    loop {
+        let (longest_idx, &(longest, _)) = sides
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .expect("there is always at least one duration");
+
+        // a small tolerance avoids near-degenerate, flat shapes
+        if longest < total * 0.499 && sides.len() >= MIN_NUM_VERTICES {
+            break;
+        }
+
+        if sides.len() >= NODE_CAPACITY {
+            return Err("Too many vertices are needed".to_string());
+        }
+
+        sides[longest_idx].0 = longest * 0.5;
+        sides.insert(longest_idx + 1, (longest * 0.5, true));
+    }
+
+    Ok(sides.into_iter().unzip())
+}
+
+/// Finds the convex polygon inscribed in a circle whose sides have the
+/// provided lengths, centred on the circle's centre. The first vertex is at
+/// the top, and the rest follow clockwise.
+///
+/// Each side of length `s` subtends an angle of `2 asin(s / 2r)` at the centre
+/// of a circle of radius `r`. If the centre lies within the polygon these
+/// angles add up to a full turn; otherwise, the angle of the longest side is
+/// equal to the sum of all the others. Either way, `r` is found by bisection.
+fn cyclic_polygon(lengths: &[f32]) -> Vec<Vec2> {
+    let lengths: Vec<f64> = lengths.iter().map(|&l| l as f64).collect();
+    let (longest_idx, longest) = lengths
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0));
+
+    let angle = |len: f64, r: f64| 2.0 * (len / (2.0 * r)).min(1.0).asin();
+    let others = |r: f64| -> f64 {
+        lengths
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != longest_idx)
+            .map(|(_, &l)| angle(l, r))
+            .sum()
+    };
+
+    let min_radius = longest * 0.5;
+    let centre_inside =
+        others(min_radius) + std::f64::consts::PI >= std::f64::consts::TAU;
+
+    // positive when the radius is too small
+    let residual = |r: f64| {
+        if centre_inside {
+            others(r) + angle(longest, r) - std::f64::consts::TAU
+        }
+        else {
+            angle(longest, r) - others(r)
+        }
+    };
+
+    let mut lo = min_radius;
+    let mut hi = lengths.iter().sum::<f64>().max(min_radius * 2.0);
+    while residual(hi) > 0.0 && hi < f64::MAX * 0.25 {
+        hi *= 2.0;
+    }
+
+    for _ in 0..100 {
+        let mid = (lo + hi) * 0.5;
+
+        if residual(mid) > 0.0 {
+            lo = mid;
+        }
+        else {
+            hi = mid;
+        }
+    }
+
+    let radius = (lo + hi) * 0.5;
+    let mut phase = std::f64::consts::FRAC_PI_2;
+
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(i, &len)| {
+            let vertex = vec2(phase.cos() as f32, phase.sin() as f32)
+                * radius as f32;
+
+            // if the centre is outside of the polygon, the longest side turns
+            // back the way the others came
+            let a = angle(len, radius);
+            phase -= if !centre_inside && i == longest_idx { -a } else { a };
+
+            vertex
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the length of each side of the polygon through `vertices`.
+    fn side_lengths(vertices: &[Vec2]) -> Vec<f32> {
+        let num = vertices.len();
+        (0..num)
+            .map(|i| vertices[i].distance(vertices[(i + 1) % num]))
+            .collect()
+    }
+
+    #[test]
+    fn parses_numbers_and_note_values() {
+        assert_eq!(parse_durations("4 2, 2 3"), Ok(vec![4.0, 2.0, 2.0, 3.0]));
+        assert_eq!(
+            parse_durations("w h q e s t"),
+            Ok(vec![16.0, 8.0, 4.0, 2.0, 1.0, 0.5])
+        );
+        assert_eq!(
+            parse_durations("q. q.. e3"),
+            Ok(vec![6.0, 7.0, 4.0 / 3.0])
+        );
+        assert!(parse_durations("q x").is_err());
+    }
+
+    #[test]
+    fn solves_polygons_with_the_given_side_lengths() {
+        // the second shape is obtuse, so the centre of its circle is outside
+        // of it
+        for lengths in [vec![4.0, 2.0, 2.0, 3.0, 3.0, 2.0], vec![3.0, 2.0, 2.0]]
+        {
+            let vertices = cyclic_polygon(&lengths);
+
+            for (side, length) in side_lengths(&vertices).iter().zip(&lengths)
+            {
+                assert!((side - length).abs() < 1e-3, "{side} != {length}");
+            }
+
+            // every vertex lies on the same circle
+            let radius = vertices[0].length();
+            assert!(vertices
+                .iter()
+                .all(|v| (v.length() - radius).abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn convex_shapes_follow_the_durations_at_the_current_size() {
+        let rect = Rect::from_w_h(1000.0, 1000.0);
+        let mut seq = Sequence::new(rect, mpsc::channel().0, 4);
+        let perimeter = seq.length;
+        let durations = [4.0, 2.0, 2.0, 3.0, 3.0, 2.0];
+
+        seq.set_durations(
+            &durations,
+            SolveOptions { convex: true, centred: true },
+        )
+        .expect("the rhythm is valid");
+
+        assert_eq!(seq.num_nodes(), durations.len());
+        assert!((seq.length - perimeter).abs() < 1.0);
+
+        let step = seq.length / durations.iter().sum::<f32>();
+        for (segment, duration) in seq.segments.iter().zip(&durations) {
+            assert!((segment.length / step - duration).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn splits_durations_too_long_for_a_polygon() {
+        let (lengths, rests) =
+            split_infeasible(&[8.0, 1.0, 1.0]).expect("the rhythm is valid");
+
+        assert_eq!(lengths, [4.0, 4.0, 1.0, 1.0]);
+        assert_eq!(rests, [false, true, false, false]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Module for Euclidean rhythm generation.
+
+use super::*;
+
+/// Generates the Euclidean rhythm `E(hits, steps)` using Bjorklund's
+/// algorithm, where `true` represents a hit and `false` a rest.
+///
+/// The pattern is rotated forwards by `rotation` steps, so a rotation of
+/// `1` delays every hit by one step. `hits` is clamped to `steps`.
+pub fn euclidean(hits: usize, steps: usize, rotation: usize) -> Vec<bool> {
+    if steps == 0 {
+        return Vec::new();
+    }
+
+    let hits = hits.min(steps);
+    if hits == 0 {
+        return vec![false; steps];
+    }
+
+    let mut front: Vec<Vec<bool>> = vec![vec![true]; hits];
+    let mut back: Vec<Vec<bool>> = vec![vec![false]; steps - hits];
+
+    // repeatedly distribute the remainder groups over the leading groups
+    // until at most one remainder group is left
+    while back.len() > 1 {
+        let num_pairs = front.len().min(back.len());
+
+        let remainder = if front.len() > num_pairs {
+            front.split_off(num_pairs)
+        }
+        else {
+            back.split_off(num_pairs)
+        };
+
+        for (group, tail) in front.iter_mut().zip(back.drain(..)) {
+            group.extend(tail);
+        }
+
+        back = remainder;
+    }
+
+    let mut pattern: Vec<bool> =
+        front.into_iter().chain(back).flatten().collect();
+    pattern.rotate_right(rotation % steps);
+
+    pattern
+}
+
+impl Sequence {
+    /// Lays the sequence out as the Euclidean rhythm `E(hits, steps)`,
+    /// rotated by `rotation` steps. See [`euclidean()`].
+    pub fn set_euclidean(
+        &mut self,
+        hits: usize,
+        steps: usize,
+        rotation: usize,
+    ) {
+        self.set_step_pattern(&euclidean(hits, steps, rotation));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts a pattern such as `x..x..x.` into hits and rests.
+    fn pattern(text: &str) -> Vec<bool> {
+        text.chars().map(|c| c == 'x').collect()
+    }
+
+    #[test]
+    fn generates_common_euclidean_rhythms() {
+        // the tresillo, cinquillo and a four-on-the-floor
+        assert_eq!(euclidean(3, 8, 0), pattern("x..x..x."));
+        assert_eq!(euclidean(5, 8, 0), pattern("x.xx.xx."));
+        assert_eq!(euclidean(4, 16, 0), pattern("x...x...x...x..."));
+    }
+
+    #[test]
+    fn spreads_hits_as_evenly_as_possible() {
+        for steps in 1..=16 {
+            for hits in 1..=steps {
+                let pattern = euclidean(hits, steps, 0);
+                let positions: Vec<usize> =
+                    (0..steps).filter(|&i| pattern[i]).collect();
+                let gaps: Vec<usize> = (0..hits)
+                    .map(|i| {
+                        let next = positions.get(i + 1).copied();
+                        next.unwrap_or(positions[0] + steps) - positions[i]
+                    })
+                    .collect();
+
+                assert_eq!(positions.len(), hits);
+                assert!(
+                    gaps.iter().max().unwrap() - gaps.iter().min().unwrap()
+                        <= 1,
+                    "E({hits}, {steps}) has gaps {gaps:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotates_hits_later() {
+        assert_eq!(euclidean(3, 8, 1), pattern(".x..x..x"));
+        assert_eq!(euclidean(3, 8, 9), euclidean(3, 8, 1));
+    }
+}
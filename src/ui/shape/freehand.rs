@@ -0,0 +1,237 @@
+//! Module for drawing shapes freehand with the mouse.
+
+use super::*;
+
+/// The shortest distance the mouse must move to add a point to a stroke.
+const MIN_STROKE_STEP: f32 = 3.0;
+/// The largest distance a dense path may stray from the stroke it follows.
+const DENSE_TOLERANCE: f32 = 2.0;
+
+/// Settings for turning a freehand stroke into a shape.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FreehandSettings {
+    /// The number of vertices which play a note.
+    pub points: usize,
+    /// Whether the shape keeps as many vertices as it needs to follow the
+    /// stroke closely, with every vertex but the trigger points as a rest.
+    pub dense: bool,
+}
+
+/// Returns the distance from `point` to the straight line from `from` to
+/// `to`.
+fn distance_to_line(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let line = to - from;
+    let len_sq = line.length_squared();
+    if len_sq <= f32::EPSILON {
+        return point.distance(from);
+    }
+
+    let interp = ((point - from).dot(line) / len_sq).clamp(0.0, 1.0);
+    point.distance(from.lerp(to, interp))
+}
+
+/// Ranks the points of a closed stroke by how much they matter to its shape,
+/// in the manner of the Ramer–Douglas–Peucker algorithm: each point is the
+/// one furthest from the path through every point ranked before it.
+///
+/// Returns the indices of the points in order of rank, with how far each one
+/// was from that path. Ranking stops once `max` points are ranked.
+fn rank_points(stroke: &[Vec2], max: usize) -> Vec<(usize, f32)> {
+    let num = stroke.len();
+    if num < 2 {
+        return (0..num).map(|i| (i, f32::INFINITY)).collect();
+    }
+
+    let point = |i: usize| stroke[i % num];
+
+    // the stroke is closed by repeating the first point at its end
+    let furthest = (1..num)
+        .max_by(|&a, &b| {
+            let start = point(0);
+            point(a).distance(start).total_cmp(&point(b).distance(start))
+        })
+        .unwrap_or(0);
+
+    let mut ranked = vec![(0, f32::INFINITY), (furthest, f32::INFINITY)];
+    let mut kept = vec![0, furthest, num];
+
+    while ranked.len() < max.min(num) {
+        let best = kept
+            .windows(2)
+            .flat_map(|span| {
+                let (from, to) = (point(span[0]), point(span[1]));
+                (span[0] + 1..span[1])
+                    .map(move |i| (i, distance_to_line(point(i), from, to)))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let Some((idx, distance)) = best else {
+            break;
+        };
+
+        ranked.push((idx, distance));
+        let pos = kept.partition_point(|&i| i < idx);
+        kept.insert(pos, idx);
+    }
+
+    ranked
+}
+
+impl Sequence {
+    /// Records a freehand stroke while the left mouse button is held, if it
+    /// was pressed inside the shape's rect.
+    pub(super) fn update_stroke(&mut self, input_data: &InputData) {
+        let mp = input_data.mouse_pos;
+
+        if input_data.left_button_just_clicked() && self.rect.contains(mp) {
+            self.stroke = vec![mp];
+            self.is_stroking = true;
+            return;
+        }
+
+        if !self.is_stroking {
+            return;
+        }
+
+        if !input_data.is_left_clicked {
+            self.is_stroking = false;
+            self.stroke_finished = true;
+            return;
+        }
+
+        let mp = mp.clamp(self.rect.bottom_left(), self.rect.top_right());
+        let moved = |last: &Vec2| last.distance(mp) >= MIN_STROKE_STEP;
+
+        if self.stroke.last().is_none_or(moved) {
+            self.stroke.push(mp);
+        }
+    }
+
+    /// Sets whether left-dragging draws a new shape rather than moving nodes.
+    pub fn set_drawing(&mut self, drawing: bool) {
+        self.is_drawing = drawing;
+        self.is_stroking = false;
+        self.stroke.clear();
+    }
+
+    /// Returns whether left-dragging draws a new shape.
+    pub fn is_drawing(&self) -> bool {
+        self.is_drawing
+    }
+
+    /// Returns the last stroke drawn, once the mouse button is released.
+    pub fn take_stroke(&mut self) -> Option<Vec<Vec2>> {
+        if !self.stroke_finished {
+            return None;
+        }
+
+        self.stroke_finished = false;
+        Some(std::mem::take(&mut self.stroke))
+    }
+
+    /// Lays the sequence out along a freehand `stroke`, which is closed back
+    /// to its start, returning the number of vertices used.
+    ///
+    /// The stroke is simplified to the points which matter most to its shape.
+    /// Unless the path is dense, those are the only vertices; otherwise,
+    /// enough vertices are kept to follow the stroke closely, and all but the
+    /// most important are rests.
+    pub fn set_freehand(
+        &mut self,
+        stroke: &[Vec2],
+        settings: FreehandSettings,
+    ) -> Result<usize, String> {
+        if stroke.len() < MIN_NUM_VERTICES {
+            return Err("The stroke is too short to make a shape".to_string());
+        }
+
+        let points = settings.points.clamp(MIN_NUM_VERTICES, NODE_CAPACITY);
+        let max = if settings.dense { NODE_CAPACITY } else { points };
+
+        let mut ranked = rank_points(stroke, max);
+        if settings.dense {
+            let num_needed = ranked
+                .iter()
+                .position(|&(_, distance)| distance < DENSE_TOLERANCE)
+                .unwrap_or(ranked.len());
+            ranked.truncate(num_needed.max(points));
+        }
+
+        if ranked.len() < MIN_NUM_VERTICES {
+            return Err("The stroke is too small to make a shape".to_string());
+        }
+
+        let mut kept: Vec<(usize, bool)> = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, &(idx, _))| (idx, rank >= points))
+            .collect();
+        kept.sort_unstable_by_key(|&(idx, _)| idx);
+
+        let vertices: Vec<Vec2> =
+            kept.iter().map(|&(idx, _)| stroke[idx]).collect();
+        let rests: Vec<bool> = kept.iter().map(|&(_, rest)| rest).collect();
+
+        self.set_vertices(&vertices, &rests);
+
+        Ok(vertices.len())
+    }
+
+    /// Draws the stroke being drawn.
+    pub(super) fn draw_stroke(&self, draw: &Draw) {
+        if self.is_stroking {
+            draw.polyline()
+                .weight(2.0)
+                .color(GREY)
+                .points(self.stroke.iter().copied());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stroke around a square with sides of length `100.0`, with a slight
+    /// wobble between its corners.
+    fn wobbly_square() -> Vec<Vec2> {
+        let corners = [
+            vec2(0.0, 0.0),
+            vec2(100.0, 0.0),
+            vec2(100.0, 100.0),
+            vec2(0.0, 100.0),
+        ];
+
+        (0..40)
+            .map(|i| {
+                let (side, step) = (i / 10, i % 10);
+                let (from, to) = (corners[side], corners[(side + 1) % 4]);
+                let wobble = if step % 2 == 0 { 0.0 } else { 1.0 };
+                let normal = (to - from).perp().normalize();
+
+                from.lerp(to, step as f32 / 10.0) + normal * wobble
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ranks_corners_first() {
+        let ranked = rank_points(&wobbly_square(), 40);
+        assert_eq!(ranked.len(), 40);
+
+        // the start, the point furthest from it, then the other two corners
+        assert_eq!(ranked[0].0, 0);
+        assert_eq!(ranked[1].0, 20);
+        let mut others = [ranked[2].0, ranked[3].0];
+        others.sort_unstable();
+        assert_eq!(others, [10, 30]);
+
+        // the wobble is all that is left once the corners are ranked
+        assert!(ranked[4..].iter().all(|&(_, distance)| distance < 1.0 + 1e-4));
+    }
+
+    #[test]
+    fn stops_ranking_at_max() {
+        assert_eq!(rank_points(&wobbly_square(), 4).len(), 4);
+    }
+}
@@ -0,0 +1,301 @@
+//! Module for graph edges, which let a playhead branch away from the outline
+//! of a shape, such as `1>3:2 3>1 2>4:0.5`.
+
+use super::*;
+
+/// The largest number of edges in a graph.
+pub const MAX_EDGES: usize = 64;
+/// How far short of each vertex an edge's arrow stops.
+const ARROW_GAP: f32 = 12.0;
+
+/// An edge which a playhead may take from one vertex to another.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// How likely the edge is to be taken, relative to the other edges which
+    /// leave the same vertex. Edges with a weight of `0.0` are never taken.
+    pub weight: f32,
+}
+
+/// How a playhead chooses which edge to take when it arrives at a vertex.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphRule {
+    /// A random edge, chosen by weight.
+    #[default]
+    Weighted,
+    /// Each edge in turn, in the order they were typed.
+    Cycle,
+}
+
+impl GraphRule {
+    /// Every rule, in the order they are shown by the UI.
+    pub const ALL: [Self; 2] = [Self::Weighted, Self::Cycle];
+
+    /// Returns the rule at `idx` in [`GraphRule::ALL`], or `Weighted` if
+    /// `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The rule's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Weighted => "Weight",
+            Self::Cycle => "Cycle",
+        }
+    }
+}
+
+/// The edges of a shape, which playheads follow in the `Graph` direction.
+///
+/// Each vertex has an edge to the next vertex around the shape with a weight
+/// of `1.0`, unless another weight is given for it.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Graph {
+    pub edges: Vec<Edge>,
+    pub rule: GraphRule,
+}
+
+impl Graph {
+    /// Returns the edges which leave `vertex` of a shape with `num` vertices
+    /// and may be taken. If there are none, the edge to the next vertex is
+    /// returned so a playhead is never stuck.
+    pub fn outgoing(
+        &self,
+        vertex: usize,
+        num: usize,
+    ) -> impl Iterator<Item = Edge> + Clone + '_ {
+        let next = (vertex + 1) % num;
+        let listed = self
+            .edges
+            .iter()
+            .copied()
+            .filter(move |edge| edge.from == vertex && edge.to < num);
+        let next_edge = Edge { from: vertex, to: next, weight: 1.0 };
+
+        let takeable = listed
+            .clone()
+            .all(|edge| edge.to != next)
+            .then_some(next_edge)
+            .into_iter()
+            .chain(listed)
+            .filter(|edge| edge.weight > 0.0);
+        let is_stuck = takeable.clone().next().is_none();
+
+        takeable.chain(is_stuck.then_some(next_edge))
+    }
+
+    /// Returns whether a playhead may take the edge from `from` to `to` of a
+    /// shape with `num` vertices.
+    pub fn has_edge(&self, from: usize, to: usize, num: usize) -> bool {
+        from < num
+            && to < num
+            && self.outgoing(from, num).any(|edge| edge.to == to)
+    }
+}
+
+/// The way between the two vertices of an edge.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Route {
+    /// Along a segment of the shape, if the vertices are neighbours.
+    Segment { segment: usize, is_forward: bool },
+    /// In a straight line across the shape.
+    Chord { start: Vec2, end: Vec2 },
+}
+
+impl Route {
+    /// Returns the way from vertex `from` to vertex `to` of the shape made
+    /// of `segments`.
+    pub fn new(from: usize, to: usize, segments: &[Segment]) -> Self {
+        let num = segments.len();
+
+        if to == (from + 1) % num {
+            Self::Segment { segment: from, is_forward: true }
+        }
+        else if from == (to + 1) % num {
+            Self::Segment { segment: to, is_forward: false }
+        }
+        else {
+            Self::Chord {
+                start: segments[from].start,
+                end: segments[to].start,
+            }
+        }
+    }
+
+    /// Returns the length of the route.
+    pub fn length(&self, segments: &[Segment]) -> f32 {
+        match *self {
+            Self::Segment { segment, .. } => segments[segment].length,
+            Self::Chord { start, end } => start.distance(end),
+        }
+    }
+
+    /// Returns the point `progress` (`0.0` to `1.0`) of the way along the
+    /// route.
+    pub fn point_at(&self, progress: f32, segments: &[Segment]) -> Vec2 {
+        match *self {
+            Self::Segment { segment, is_forward } => segments[segment]
+                .point_at(if is_forward { progress } else { 1.0 - progress }),
+            Self::Chord { start, end } => start.lerp(end, progress),
+        }
+    }
+}
+
+/// Parses a list of edges such as `1>3:2 3>1 2>4:0.5`, where `1>3:2` is an
+/// edge from the first vertex to the third with a weight of `2`. Weights are
+/// `1` if left out.
+///
+/// Edges are separated by whitespace or commas. An edge listed twice keeps
+/// its last weight. An empty list clears the edges.
+pub fn parse_edges(text: &str) -> Result<Vec<Edge>, String> {
+    let mut edges: Vec<Edge> = Vec::new();
+
+    for token in text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+    {
+        let invalid = || format!("Invalid edge \"{token}\"");
+
+        let (vertices, weight) = match token.split_once(':') {
+            Some((vertices, weight)) => {
+                let weight: f32 = weight.parse().map_err(|_| invalid())?;
+                (vertices, weight)
+            }
+            None => (token, 1.0),
+        };
+
+        let (from, to) = vertices.split_once('>').ok_or_else(invalid)?;
+        let vertex = |text: &str| match text.parse::<usize>() {
+            Ok(num) if num >= 1 => Ok(num - 1),
+            _ => Err(invalid()),
+        };
+        let (from, to) = (vertex(from)?, vertex(to)?);
+
+        if from == to {
+            return Err(format!("Edge \"{token}\" joins a vertex to itself"));
+        }
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!(
+                "Edge \"{token}\" needs a weight of 0 or more"
+            ));
+        }
+
+        match edges.iter_mut().find(|e| e.from == from && e.to == to) {
+            Some(edge) => edge.weight = weight,
+            None => edges.push(Edge { from, to, weight }),
+        }
+    }
+
+    if edges.len() > MAX_EDGES {
+        return Err(format!("Graphs are limited to {MAX_EDGES} edges"));
+    }
+
+    Ok(edges)
+}
+
+impl Sequence {
+    /// Sets the edges which playheads follow in the `Graph` direction, as
+    /// well as the edge to the next vertex around the shape.
+    pub fn set_edges(&mut self, edges: Vec<Edge>) -> Result<(), String> {
+        let num = self.num_nodes;
+
+        if let Some(edge) = edges.iter().find(|e| e.from.max(e.to) >= num) {
+            return Err(format!(
+                "Vertex {} doesn't exist",
+                edge.from.max(edge.to) + 1
+            ));
+        }
+
+        self.graph.edges = edges;
+
+        Ok(())
+    }
+
+    /// Sets how playheads choose which edge to take in the `Graph`
+    /// direction.
+    pub fn set_graph_rule(&mut self, rule: GraphRule) {
+        self.graph.rule = rule;
+    }
+
+    /// Draws an arrow for each edge which may be taken, if the playheads
+    /// follow them. Heavier edges are drawn thicker.
+    pub(super) fn draw_edges(&self, draw: &Draw) {
+        if self.direction != PlayDirection::Graph {
+            return;
+        }
+
+        let num = self.num_nodes;
+        let edges = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.weight > 0.0 && edge.from.max(edge.to) < num);
+        let max_weight = edges.clone().fold(0.0, |max, e| e.weight.max(max));
+
+        for edge in edges {
+            let start = self.nodes[edge.from].pos;
+            let end = self.nodes[edge.to].pos;
+            let gap = (end - start).normalize_or_zero() * ARROW_GAP;
+
+            draw.arrow()
+                .start(start + gap)
+                .end(end - gap)
+                .weight(3.0f32.mul_add(edge.weight / max_weight, 1.0))
+                .color(GREY);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the vertices a playhead may head to from `vertex` of a shape
+    /// with `num` vertices, with their weights.
+    fn targets(graph: &Graph, vertex: usize, num: usize) -> Vec<(usize, f32)> {
+        graph
+            .outgoing(vertex, num)
+            .map(|edge| (edge.to, edge.weight))
+            .collect()
+    }
+
+    #[test]
+    fn parses_edges_with_and_without_weights() {
+        assert_eq!(
+            parse_edges("1>3:2 3>1, 2>4:0.5 1>3:3"),
+            Ok(vec![
+                Edge { from: 0, to: 2, weight: 3.0 },
+                Edge { from: 2, to: 0, weight: 1.0 },
+                Edge { from: 1, to: 3, weight: 0.5 },
+            ])
+        );
+        assert!(parse_edges("1>1").is_err());
+        assert!(parse_edges("1>2:-1").is_err());
+    }
+
+    #[test]
+    fn branches_alongside_the_next_vertex() {
+        let graph = Graph {
+            edges: parse_edges("1>3:2").unwrap(),
+            ..Graph::default()
+        };
+
+        assert_eq!(targets(&graph, 0, 4), [(1, 1.0), (2, 2.0)]);
+        assert_eq!(targets(&graph, 1, 4), [(2, 1.0)]);
+    }
+
+    #[test]
+    fn zero_weights_remove_edges_but_never_strand_a_playhead() {
+        let graph = Graph {
+            edges: parse_edges("1>2:0 1>3:0 2>1").unwrap(),
+            ..Graph::default()
+        };
+
+        assert_eq!(targets(&graph, 0, 4), [(1, 1.0)]);
+        assert_eq!(targets(&graph, 1, 4), [(2, 1.0), (0, 1.0)]);
+        assert!(!graph.has_edge(0, 2, 4));
+        assert!(graph.has_edge(1, 0, 4));
+    }
+}
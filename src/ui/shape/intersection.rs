@@ -0,0 +1,237 @@
+//! Module for the points where a shape's segments cross each other.
+
+use super::*;
+
+/// The largest number of points where two segments cross which are tracked.
+/// Two cubic curves cross at most 9 times, so every crossing of a shape is
+/// tracked however many vertices it has.
+const MAX_CROSSINGS_PER_PAIR: usize = 9;
+/// The default MIDI note played at crossing points.
+pub const DEFAULT_CROSSING_NOTE: f32 = 62.0;
+
+/// A point where a segment crosses another segment.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Crossing {
+    /// The segment the point lies on.
+    pub segment: usize,
+    /// The position of the point, as an interpolation value between the
+    /// segment's start and end.
+    pub interp: f32,
+    pub pos: Vec2,
+}
+
+/// The distance from either end of a segment, as an interpolation value,
+/// within which crossings are ignored (as they are at a vertex).
+const TOLERANCE: f32 = 1e-4;
+
+/// Returns where the straight lines from `a0` to `a1` and from `b0` to `b1`
+/// cross, as interpolation values along each, if they cross.
+pub(super) fn intersect(
+    a0: Vec2,
+    a1: Vec2,
+    b0: Vec2,
+    b1: Vec2,
+) -> Option<(f32, f32)> {
+    let da = a1 - a0;
+    let db = b1 - b0;
+    let denom = da.perp_dot(db);
+
+    // parallel lines are treated as never crossing
+    if denom.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let offset = b0 - a0;
+    let ta = offset.perp_dot(db) / denom;
+    let tb = offset.perp_dot(da) / denom;
+
+    // the start of each line is included, but its end isn't, so a crossing
+    // where two lines of a curve meet is only found once
+    let inside = |t: f32| (0.0..1.0).contains(&t);
+    (inside(ta) && inside(tb)).then_some((ta, tb))
+}
+
+/// Returns the sample points of `segment` (see [`Segment::sample_points()`])
+/// and how many there are, without allocating.
+fn sample_points(
+    segment: &Segment,
+) -> ([(Vec2, f32); CURVE_RESOLUTION + 1], usize) {
+    let mut points = [(Vec2::ZERO, 0.0); CURVE_RESOLUTION + 1];
+    let mut num = 0;
+
+    for (point, sample) in points.iter_mut().zip(segment.sample_points()) {
+        *point = sample;
+        num += 1;
+    }
+
+    (points, num)
+}
+
+/// Returns where the segments `a` and `b` cross, as interpolation values along
+/// each, anywhere but their ends. Curved segments are treated as the straight
+/// lines between their sample points.
+///
+/// At most [`MAX_CROSSINGS_PER_PAIR`] crossings are returned.
+fn segment_crossings(
+    a: &Segment,
+    b: &Segment,
+) -> impl Iterator<Item = (f32, f32)> {
+    let (points_a, num_a) = sample_points(a);
+    let (points_b, num_b) = sample_points(b);
+
+    let mut crossings = [(0.0, 0.0); MAX_CROSSINGS_PER_PAIR];
+    let mut num = 0;
+
+    'outer: for piece_a in points_a[..num_a].windows(2) {
+        let [(a0, fa0), (a1, fa1)] = [piece_a[0], piece_a[1]];
+
+        for piece_b in points_b[..num_b].windows(2) {
+            let [(b0, fb0), (b1, fb1)] = [piece_b[0], piece_b[1]];
+
+            let Some((ta, tb)) = intersect(a0, a1, b0, b1) else {
+                continue;
+            };
+
+            let (ia, ib) = (lerp(fa0, fa1, ta), lerp(fb0, fb1, tb));
+            let inside = |t: f32| TOLERANCE < t && t < 1.0 - TOLERANCE;
+
+            if inside(ia) && inside(ib) {
+                crossings[num] = (ia, ib);
+                num += 1;
+
+                if num == MAX_CROSSINGS_PER_PAIR {
+                    break 'outer;
+                }
+            }
+        }
+    }
+
+    crossings.into_iter().take(num)
+}
+
+impl Sequence {
+    /// Finds every point where two segments of the shape cross. Each point is
+    /// stored once for each of the two segments.
+    pub(super) fn update_crossings(&mut self) {
+        self.crossings.clear();
+
+        if !self.crossings_enabled {
+            return;
+        }
+
+        let segments = &self.segments[..self.num_nodes];
+
+        for (i, a) in segments.iter().enumerate() {
+            for (j, b) in segments.iter().enumerate().skip(i + 1) {
+                if a.curve.is_gap || b.curve.is_gap {
+                    continue;
+                }
+
+                for (ta, tb) in segment_crossings(a, b) {
+                    let pos = a.point_at(ta);
+
+                    self.crossings.extend([
+                        Crossing { segment: i, interp: ta, pos },
+                        Crossing { segment: j, interp: tb, pos },
+                    ]);
+                }
+            }
+        }
+    }
+
+    /// Sets whether notes are played where the shape crosses itself.
+    pub fn set_crossings_enabled(&mut self, enabled: bool) {
+        self.crossings_enabled = enabled;
+        self.update_crossings();
+    }
+
+    /// Sets the MIDI note played where the shape crosses itself.
+    pub fn set_crossing_note(&mut self, note: f32) {
+        self.crossing_note = note;
+    }
+
+    /// Plays a note for each crossing point passed along `sweep`.
+    pub(super) fn trigger_crossings(&self, sweep: &Sweep, transpose: f32) {
+        for crossing in &self.crossings {
+            if crossing.segment != sweep.segment
+                || !sweep.crosses(crossing.interp)
+            {
+                continue;
+            }
+
+            let note_data = NoteEventData::new(self.crossing_note + transpose);
+
+            self.note_data_sender
+                .send(note_data)
+                .expect("failed to send note data");
+        }
+    }
+
+    /// Draws a small cross at each crossing point.
+    pub(super) fn draw_crossings(&self, draw: &Draw) {
+        const HALF_SIZE: f32 = 5.0;
+
+        // each point is stored twice, once for each segment
+        for crossing in self.crossings.iter().step_by(2) {
+            for dir in [vec2(1.0, 1.0), vec2(1.0, -1.0)] {
+                draw.line()
+                    .start(crossing.pos - dir * HALF_SIZE)
+                    .end(crossing.pos + dir * HALF_SIZE)
+                    .color(DARKGREEN)
+                    .weight(2.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: Vec2, end: Vec2) -> Segment {
+        let mut segment = Segment { start, end, ..Segment::default() };
+        segment.calculate_distance();
+        segment
+    }
+
+    fn crossings(a: &Segment, b: &Segment) -> Vec<(f32, f32)> {
+        segment_crossings(a, b).collect()
+    }
+
+    #[test]
+    fn finds_where_straight_segments_cross() {
+        let a = segment(vec2(-10.0, 0.0), vec2(10.0, 0.0));
+        let b = segment(vec2(0.0, -10.0), vec2(0.0, 30.0));
+
+        let found = crossings(&a, &b);
+        assert_eq!(found.len(), 1);
+        assert!((found[0].0 - 0.5).abs() < 1e-5);
+        assert!((found[0].1 - 0.25).abs() < 1e-5);
+
+        // segments which only meet at a vertex don't cross
+        let c = segment(vec2(10.0, 0.0), vec2(0.0, 10.0));
+        assert!(crossings(&a, &c).is_empty());
+    }
+
+    #[test]
+    fn finds_where_curved_segments_cross() {
+        // an arch, which crosses a line above its chord twice
+        let mut arch = segment(vec2(-30.0, 0.0), vec2(30.0, 0.0));
+        arch.curve.kind = CurveKind::Cubic;
+        arch.curve.handles = [vec2(0.0, 80.0), vec2(0.0, 80.0)];
+        arch.calculate_distance();
+        let line = segment(vec2(-50.0, 30.0), vec2(50.0, 30.0));
+
+        let found = crossings(&arch, &line);
+        assert_eq!(found.len(), 2);
+
+        // the arch is symmetrical, so the crossings are too
+        let (first, second) = (found[0], found[1]);
+        assert!((first.0 + second.0 - 1.0).abs() < 1e-3);
+        assert!((first.1 + second.1 - 1.0).abs() < 1e-3);
+
+        for &(ta, tb) in &found {
+            assert!(arch.point_at(ta).distance(line.point_at(tb)) < 0.5);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Module for the built-in library of named rhythm shapes.
+
+use super::*;
+
+const SAM: f32 = 81.0;
+const TALI: f32 = 76.0;
+const KHALI: f32 = 64.0;
+const ACCENT: f32 = 81.0;
+const BEAT: f32 = 69.0;
+const OFFBEAT: f32 = 64.0;
+
+/// A named rhythm, stored as a vertex layout on the perimeter of a regular
+/// polygon along with the pitch of each vertex.
+#[derive(Clone, Copy, Debug)]
+pub struct RhythmShape {
+    /// The name of the rhythm.
+    pub name: &'static str,
+    /// The number of equal steps (sides) around the shape's perimeter.
+    pub steps: usize,
+    /// The step of each vertex which triggers a note, and the MIDI note it
+    /// plays. All other steps are rests.
+    pub hits: &'static [(usize, f32)],
+}
+
+/// The built-in rhythm shapes.
+pub const RHYTHM_LIBRARY: &[RhythmShape] = &[
+    RhythmShape {
+        name: "Son clave (3-2)",
+        steps: 16,
+        hits: &[(0, ACCENT), (3, BEAT), (6, BEAT), (10, BEAT), (12, BEAT)],
+    },
+    RhythmShape {
+        name: "Son clave (2-3)",
+        steps: 16,
+        hits: &[(2, ACCENT), (4, BEAT), (8, BEAT), (11, BEAT), (14, BEAT)],
+    },
+    RhythmShape {
+        name: "Rumba clave (3-2)",
+        steps: 16,
+        hits: &[(0, ACCENT), (3, BEAT), (7, BEAT), (10, BEAT), (12, BEAT)],
+    },
+    RhythmShape {
+        name: "Rumba clave (2-3)",
+        steps: 16,
+        hits: &[(2, ACCENT), (4, BEAT), (8, BEAT), (11, BEAT), (15, BEAT)],
+    },
+    RhythmShape {
+        name: "Tresillo",
+        steps: 8,
+        hits: &[(0, ACCENT), (3, BEAT), (6, BEAT)],
+    },
+    RhythmShape {
+        name: "Bossa nova",
+        steps: 16,
+        hits: &[(0, ACCENT), (3, BEAT), (6, BEAT), (10, BEAT), (13, BEAT)],
+    },
+    RhythmShape {
+        name: "Shiko",
+        steps: 16,
+        hits: &[(0, ACCENT), (4, BEAT), (6, BEAT), (10, BEAT), (12, BEAT)],
+    },
+    RhythmShape {
+        name: "Soukous",
+        steps: 16,
+        hits: &[(0, ACCENT), (3, BEAT), (6, BEAT), (10, BEAT), (11, BEAT)],
+    },
+    RhythmShape {
+        name: "Gahu",
+        steps: 16,
+        hits: &[(0, ACCENT), (3, BEAT), (6, BEAT), (10, BEAT), (14, BEAT)],
+    },
+    RhythmShape {
+        name: "Swing eighths",
+        steps: 12,
+        hits: &[
+            (0, ACCENT),
+            (2, OFFBEAT),
+            (3, BEAT),
+            (5, OFFBEAT),
+            (6, BEAT),
+            (8, OFFBEAT),
+            (9, BEAT),
+            (11, OFFBEAT),
+        ],
+    },
+    // tala cycles mark the start of each vibhag (section): the sam (first
+    // beat), tali (clapped) and khali ("empty", waved) beats each have
+    // their own pitch.
+    RhythmShape {
+        name: "Dadra tala (3+3)",
+        steps: 6,
+        hits: &[(0, SAM), (3, KHALI)],
+    },
+    RhythmShape {
+        name: "Rupak tala (3+2+2)",
+        steps: 7,
+        hits: &[(0, KHALI), (3, TALI), (5, TALI)],
+    },
+    RhythmShape {
+        name: "Jhaptal (2+3+2+3)",
+        steps: 10,
+        hits: &[(0, SAM), (2, TALI), (5, KHALI), (7, TALI)],
+    },
+    RhythmShape {
+        name: "Ektal (2+2+2+2+2+2)",
+        steps: 12,
+        hits: &[
+            (0, SAM),
+            (2, KHALI),
+            (4, TALI),
+            (6, KHALI),
+            (8, TALI),
+            (10, TALI),
+        ],
+    },
+    RhythmShape {
+        name: "Tintal (4+4+4+4)",
+        steps: 16,
+        hits: &[(0, SAM), (4, TALI), (8, KHALI), (12, TALI)],
+    },
+];
+
+impl RhythmShape {
+    /// Returns the shape's layout as a step pattern, where `true` steps hold
+    /// a vertex which triggers a note.
+    pub fn pattern(&self) -> Vec<bool> {
+        let mut pattern = vec![false; self.steps];
+
+        for &(step, _) in self.hits {
+            if let Some(s) = pattern.get_mut(step) {
+                *s = true;
+            }
+        }
+
+        pattern
+    }
+}
+
+impl Sequence {
+    /// Lays the sequence out as `shape`, including the pitch of each vertex.
+    pub fn load_shape(&mut self, shape: &RhythmShape) {
+        self.set_step_pattern(&shape.pattern());
+
+        for &(step, note) in shape.hits {
+            if let Some(node) = self.nodes.get_mut(step) {
+                node.note_data_mut().note = note;
+            }
+        }
+    }
+}
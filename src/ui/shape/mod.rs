@@ -3,32 +3,105 @@
 use std::sync::{mpsc, Arc};
 
 use super::*;
+use crate::sequencer::{Clock, Groove, ShapeTiming};
 use crate::{audio::voice::NoteEventData, prelude::*};
 
+mod ball;
+mod curve;
+mod drift;
+mod durations;
+mod euclid;
+mod freehand;
+mod graph;
+mod intersection;
+mod library;
+mod morph;
 mod node;
+mod path;
+mod physics;
+mod playhead;
+mod radial;
+mod random;
+mod ratchet;
+mod sensor;
+mod snap;
+mod steps;
+mod ticks;
+mod trigger;
+use ball::Ball;
+use curve::{Curve, CURVE_RESOLUTION};
+use graph::{Graph, Route};
+use intersection::{intersect, Crossing};
+use morph::Snapshot;
 use node::Node;
+use path::EllipseArc;
+use physics::Springs;
+use playhead::{Playhead, Sweep};
+use sensor::Sensor;
+use ticks::tick_points;
+use trigger::TrigHistory;
+
+pub use ball::{BallSettings, Engine, MAX_BALLS};
+pub use curve::CurveKind;
+pub use drift::{DriftMode, DriftSettings};
+pub use durations::{parse_durations, SolveOptions};
+pub use euclid::euclidean;
+pub use freehand::FreehandSettings;
+pub use graph::{parse_edges, Edge, GraphRule, MAX_EDGES};
+pub use intersection::DEFAULT_CROSSING_NOTE;
+pub use library::{RhythmShape, RHYTHM_LIBRARY};
+pub use morph::{MorphSettings, MAX_SNAPSHOTS};
+pub use path::{PathKind, PathSettings, MAX_PATH_RADIUS};
+pub use physics::PhysicsSettings;
+pub use playhead::{PlayDirection, PlayheadSettings, MAX_PLAYHEADS};
+pub use random::RandomSettings;
+pub use ratchet::{Ratchet, RatchetSpacing, MAX_RATCHET_COUNT};
+pub use sensor::{DEFAULT_SENSOR_NOTE, DEFAULT_SENSOR_RADIUS, MAX_SENSORS};
+pub use snap::SnapGrid;
+pub use steps::{parse_steps, Step};
+pub use ticks::{TickOffset, Ticks, MAX_TICKS, MAX_TICK_GRID};
+pub use trigger::{TrigCondition, Trigger};
 
 const MIN_NUM_VERTICES: usize = 3;
 const MAX_NUM_VERTICES: usize = 8;
+/// The maximum number of nodes a sequence can hold, including rests.
+pub const NODE_CAPACITY: usize = 32;
+/// The radius of the regular polygons used to lay out nodes.
+const SHAPE_RADIUS: f32 = 250.0;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug)]
 struct Segment {
     pub start: Vec2,
     pub end: Vec2,
+    /// The length of the segment, measured along its curve.
     pub length: f32,
+    pub curve: Curve,
+    /// The length of the curve up to each of its sample points.
+    pub arc_lengths: [f32; CURVE_RESOLUTION + 1],
 }
 
-impl Segment {
-    pub fn calculate_distance(&mut self) {
-        self.length = self.start.distance(self.end);
+impl Default for Segment {
+    fn default() -> Self {
+        Self {
+            start: Vec2::ZERO,
+            end: Vec2::ZERO,
+            length: 0.0,
+            curve: Curve::default(),
+            arc_lengths: [0.0; CURVE_RESOLUTION + 1],
+        }
     }
+}
 
+impl Segment {
     pub fn draw(&self, draw: &Draw) {
-        draw.line()
-            .start(self.start)
-            .end(self.end)
+        if self.curve.is_gap {
+            return;
+        }
+
+        draw.polyline()
+            .weight(3.0)
             .color(BLACK)
-            .weight(3.0);
+            .points(self.sample_points().map(|(point, _)| point));
     }
 }
 
@@ -37,19 +110,109 @@ pub struct Sequence {
     /// All the vertices of the shape.
     nodes: Vec<Node>,
     clicked_idx: Option<usize>,
+    /// The segment and handle being dragged, if any.
+    dragged_handle: Option<(usize, usize)>,
+    /// The node which was clicked most recently.
+    selected_idx: usize,
+    /// The grid which dragged nodes are locked to.
+    snap_grid: SnapGrid,
+    /// The valid positions of the node being dragged, if it is locked to a
+    /// grid.
+    snap_guides: Vec<Vec2>,
     /// All the segments connecting each vertex.
     segments: Vec<Segment>,
     /// The length of the whole shape.
     length: f32,
-
-    last_behind_idx: usize,
-
-    /// The sequence's progress in the range `[0, 1)`.
-    progress: f32,
-    /// The position of the "playhead".
-    progress_node: Vec2,
-    /// The tempo of the sequence in beats per minute (BPM).
-    tempo: f32,
+    /// The points where segments cross each other, if they play notes.
+    crossings: Vec<Crossing>,
+    /// Whether notes are played where the shape crosses itself.
+    crossings_enabled: bool,
+    /// The MIDI note played where the shape crosses itself.
+    crossing_note: f32,
+
+    /// The playheads which travel around the shape. There is always at least
+    /// one.
+    playheads: Vec<Playhead>,
+    /// The time of the clock shared by every playhead, in loops.
+    clock: f64,
+    /// The time of the master clock, in bars.
+    master_bars: f64,
+    /// The length of a bar of the master clock, in seconds.
+    secs_per_bar: f32,
+    /// How the shape's loop lines up with the master clock.
+    timing: ShapeTiming,
+    direction: PlayDirection,
+    /// The edges which the playheads follow in the `Graph` direction.
+    graph: Graph,
+    /// The groove which warps the playhead's speed.
+    groove: Groove,
+
+    /// The random number generator for trigger probabilities.
+    trigger_rng: Rng,
+    trigger_seed: u32,
+    /// Whether the conditional triggers played, for `PRE` and `NEI`
+    /// conditions.
+    trig_history: TrigHistory,
+    /// Whether fill is active, for fill trigger conditions.
+    fill: bool,
+    /// The number of steps in the grid of ticks around the shape, or `None`
+    /// if each segment has its own number of ticks.
+    tick_grid: Option<u32>,
+
+    /// How the vertices wander over time.
+    drift: DriftSettings,
+    /// How far each vertex has wandered from where it was placed.
+    drift_offsets: Vec<Vec2>,
+    /// How many cycles of their noise curves the vertices have followed.
+    drift_phase: f64,
+    /// The random number generator for random walks.
+    drift_rng: Rng,
+    /// Whether the vertices are held where they are.
+    drift_frozen: bool,
+
+    /// Where the hand of the radial clock turns around.
+    radial_centre: Vec2,
+    /// The time of the shape's clock when the radial clock's hand last moved,
+    /// in loops.
+    radial_clock: f64,
+    /// What plays the notes of the shape.
+    ball_settings: BallSettings,
+    /// The balls which bounce around inside the shape.
+    balls: Vec<Ball>,
+    /// The time of the shape's clock when the balls last moved, in loops.
+    ball_clock: f64,
+
+    /// The springs which move the vertices, if physics is on.
+    springs: Springs,
+    /// The positions of the vertices while physics steps them, kept between
+    /// updates to avoid allocating.
+    spring_positions: Vec<Vec2>,
+
+    /// The stored layouts of the shape, which it can morph between.
+    snapshots: Vec<Option<Snapshot>>,
+    morph: MorphSettings,
+    /// How far the shape is morphed, which moves towards the morph's amount.
+    morph_amount: f32,
+    /// The time of the master clock when the morph last moved, in bars.
+    morph_clock: f64,
+    /// Whether the shape needs laying out again from the snapshots.
+    morph_changed: bool,
+
+    /// Whether left-dragging draws a new shape rather than moving nodes.
+    is_drawing: bool,
+    /// Whether a freehand stroke is being drawn.
+    is_stroking: bool,
+    /// Whether the stroke is finished and ready to become the shape.
+    stroke_finished: bool,
+    /// The points of the freehand stroke.
+    stroke: Vec<Vec2>,
+
+    /// The free-floating trigger points around the shape.
+    sensors: Vec<Sensor>,
+    /// The distance within which a playhead triggers a sensor.
+    sensor_radius: f32,
+    /// The MIDI note played by sensors.
+    sensor_note: f32,
 
     /// The number of vertices.
     num_nodes: usize,
@@ -65,18 +228,64 @@ impl Sequence {
         rect: Rect,
         note_data_sender: mpsc::Sender<NoteEventData>,
         num_init_nodes: usize,
-        tempo: f32,
     ) -> Self {
         let mut s = Self {
-            nodes: vec![Node::new(rect); MAX_NUM_VERTICES],
+            nodes: vec![Node::new(rect); NODE_CAPACITY],
             clicked_idx: None,
-            segments: vec![Segment::default(); MAX_NUM_VERTICES],
+            dragged_handle: None,
+            selected_idx: 0,
+            snap_grid: SnapGrid::Off,
+            snap_guides: Vec::new(),
+            segments: vec![Segment::default(); NODE_CAPACITY],
             length: 0.0,
-            last_behind_idx: 0,
-
-            progress: 0.0,
-            progress_node: Vec2::ZERO,
-            tempo,
+            crossings: Vec::new(),
+            crossings_enabled: false,
+            crossing_note: DEFAULT_CROSSING_NOTE,
+
+            playheads: Vec::with_capacity(MAX_PLAYHEADS),
+            clock: 0.0,
+            master_bars: 0.0,
+            secs_per_bar: 0.0,
+            timing: ShapeTiming::default(),
+            direction: PlayDirection::Forward,
+            graph: Graph::default(),
+            groove: Groove::straight(),
+
+            trigger_rng: Rng::new(0),
+            trigger_seed: 0,
+            trig_history: TrigHistory::default(),
+            fill: false,
+            tick_grid: None,
+
+            drift: DriftSettings::default(),
+            drift_offsets: vec![Vec2::ZERO; NODE_CAPACITY],
+            drift_phase: 0.0,
+            drift_rng: Rng::new(0),
+            drift_frozen: false,
+
+            radial_centre: Vec2::ZERO,
+            radial_clock: 0.0,
+            ball_settings: BallSettings::default(),
+            balls: Vec::with_capacity(MAX_BALLS),
+            ball_clock: 0.0,
+
+            springs: Springs::default(),
+            spring_positions: Vec::with_capacity(NODE_CAPACITY),
+
+            snapshots: vec![None; MAX_SNAPSHOTS],
+            morph: MorphSettings::default(),
+            morph_amount: 0.0,
+            morph_clock: 0.0,
+            morph_changed: false,
+
+            is_drawing: false,
+            is_stroking: false,
+            stroke_finished: false,
+            stroke: Vec::new(),
+
+            sensors: Vec::with_capacity(MAX_SENSORS),
+            sensor_radius: DEFAULT_SENSOR_RADIUS,
+            sensor_note: DEFAULT_SENSOR_NOTE,
 
             num_nodes: num_init_nodes,
 
@@ -88,10 +297,9 @@ impl Sequence {
         s.emplace_nodes();
         s.update_segments();
         s.update_length();
+        s.reset_node_data();
 
-        s.progress_node = s.nodes[0].pos;
-        s.nodes[0].note_data_mut().note += 12.0;
-        s.nodes[0].color = Rgba::new(1.0, 0.0, 1.0, 1.0);
+        s.playheads.push(Playhead::new(0, s.nodes[0].pos));
 
         s
     }
@@ -99,26 +307,243 @@ impl Sequence {
     /// Sets the number of active nodes in the sequence (i.e., the number of vertices).
     ///
     /// Value is clamped between [`MIN_NUM_VERTICES`] and [`MAX_NUM_VERTICES`].
+    /// Any rests are cleared, so every vertex is a hit.
     pub fn set_num_nodes(&mut self, num: usize) {
         self.num_nodes = num.clamp(MIN_NUM_VERTICES, MAX_NUM_VERTICES);
+
+        for node in &mut self.nodes {
+            node.is_rest = false;
+        }
+
         self.emplace_nodes();
+        self.straighten_segments();
         self.update_segments();
         self.update_length();
+        self.reset_node_data();
+        self.reset_drift();
+        self.reset_physics();
+        self.reset_balls();
+        self.reset_radial();
     }
 
-    /// Sets the tempo (speed) of the sequencer.
-    pub fn set_tempo(&mut self, bpm: f32) {
-        self.tempo = bpm;
+    /// Lays the sequence out as a regular polygon with one vertex per step,
+    /// where `false` steps become rests: vertices which shape the path but
+    /// never trigger a note.
+    ///
+    /// As every side of the polygon has the same length, the time between
+    /// two hits is exactly proportional to the number of steps between them.
+    pub fn set_step_pattern(&mut self, pattern: &[bool]) {
+        let num = pattern.len().clamp(MIN_NUM_VERTICES, NODE_CAPACITY);
+        let vertices: Vec<Vec2> =
+            (0..num).map(|i| regular_polygon_point(i, num)).collect();
+        let rests: Vec<bool> = (0..num)
+            .map(|i| !pattern.get(i).copied().unwrap_or(false))
+            .collect();
+
+        self.set_vertices(&vertices, &rests);
     }
 
-    /// Resets the sequence — the position of nodes and its progress.
+    /// Lays the sequence out with a vertex at each of `vertices`, which are
+    /// clamped to the sequence's bounding rect. Vertices whose entry in
+    /// `rests` is `true` become rests.
+    ///
+    /// Does nothing if there are fewer than [`MIN_NUM_VERTICES`] vertices, and
+    /// ignores any beyond [`NODE_CAPACITY`].
+    pub fn set_vertices(&mut self, vertices: &[Vec2], rests: &[bool]) {
+        if vertices.len() < MIN_NUM_VERTICES {
+            return;
+        }
+
+        self.num_nodes = vertices.len().min(NODE_CAPACITY);
+
+        for (i, node) in self.nodes.iter_mut().take(self.num_nodes).enumerate()
+        {
+            node.pos = vertices[i]
+                .clamp(self.rect.bottom_left(), self.rect.top_right());
+            node.is_rest = rests.get(i).copied().unwrap_or(false);
+        }
+
+        self.straighten_segments();
+        self.update_segments();
+        self.update_length();
+        self.reset_node_data();
+        self.reset_drift();
+        self.reset_physics();
+        self.reset_balls();
+        self.reset_radial();
+    }
+
+    /// Returns the number of vertices in the shape, including rests.
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Sets the state of the master clock, which the shape follows from the
+    /// next update.
+    pub fn set_clock(&mut self, clock: &Clock) {
+        self.master_bars = clock.bars();
+        self.secs_per_bar = clock.secs_per_bar();
+    }
+
+    /// Sets how the shape's loop lines up with the master clock. The
+    /// playheads move straight to their new positions, without playing
+    /// anything in between.
+    pub fn set_timing(&mut self, timing: ShapeTiming) {
+        self.timing = timing;
+        self.clock = timing.loops_at(self.master_bars);
+
+        for playhead in &mut self.playheads {
+            playhead.set_settings(playhead.settings(), self.clock);
+        }
+    }
+
+    /// Sets the groove which warps the playhead's speed. The groove's grid
+    /// spans one loop of the shape.
+    pub fn set_groove(&mut self, groove: Groove) {
+        self.groove = groove;
+    }
+
+    /// Sets the direction the playheads travel around the shape in.
+    pub fn set_direction(&mut self, direction: PlayDirection) {
+        self.direction = direction;
+
+        for playhead in &mut self.playheads {
+            playhead.set_direction(direction);
+        }
+    }
+
+    /// Adds a playhead to the shape, returning its index, or `None` if the
+    /// shape already has [`MAX_PLAYHEADS`] playheads.
+    ///
+    /// The new playhead starts at the same time as the others.
+    pub fn add_playhead(&mut self) -> Option<usize> {
+        let idx = self.playheads.len();
+        if idx >= MAX_PLAYHEADS {
+            return None;
+        }
+
+        let mut playhead = Playhead::new(idx, self.nodes[0].pos);
+        playhead.set_direction(self.direction);
+        playhead.set_settings(PlayheadSettings::default(), self.clock);
+        self.playheads.push(playhead);
+
+        Some(idx)
+    }
+
+    /// Removes the last playhead added to the shape, returning whether one
+    /// was removed. The first playhead is never removed.
+    pub fn remove_playhead(&mut self) -> bool {
+        if self.playheads.len() <= 1 {
+            return false;
+        }
+
+        self.playheads.pop();
+        true
+    }
+
+    /// Returns the number of playheads on the shape.
+    pub fn num_playheads(&self) -> usize {
+        self.playheads.len()
+    }
+
+    /// Returns the settings of the `idx`th playhead, if it exists.
+    pub fn playhead_settings(&self, idx: usize) -> Option<PlayheadSettings> {
+        self.playheads.get(idx).map(Playhead::settings)
+    }
+
+    /// Sets the settings of the `idx`th playhead, if it exists.
+    pub fn set_playhead_settings(
+        &mut self,
+        idx: usize,
+        settings: PlayheadSettings,
+    ) {
+        if let Some(playhead) = self.playheads.get_mut(idx) {
+            playhead.set_settings(settings, self.clock);
+        }
+    }
+
+    /// Returns the index of the node which was clicked most recently.
+    pub fn selected_node(&self) -> usize {
+        self.selected_idx.min(self.num_nodes - 1)
+    }
+
+    /// Returns the trigger of the `idx`th node, if it exists.
+    pub fn trigger(&self, idx: usize) -> Option<Trigger> {
+        self.nodes[..self.num_nodes].get(idx).map(|node| node.trigger)
+    }
+
+    /// Sets the trigger of the `idx`th node, if it exists.
+    pub fn set_trigger(&mut self, idx: usize, trigger: Trigger) {
+        if let Some(node) = self.nodes[..self.num_nodes].get_mut(idx) {
+            node.trigger = trigger;
+        }
+    }
+
+    /// Returns the ratchet of the `idx`th node, if it exists.
+    pub fn ratchet(&self, idx: usize) -> Option<Ratchet> {
+        self.nodes[..self.num_nodes].get(idx).map(|node| node.ratchet)
+    }
+
+    /// Sets the ratchet of the `idx`th node, if it exists.
+    pub fn set_ratchet(&mut self, idx: usize, ratchet: Ratchet) {
+        if let Some(node) = self.nodes[..self.num_nodes].get_mut(idx) {
+            node.ratchet = ratchet;
+        }
+    }
+
+    /// Returns the ticks on the segment which starts at the `idx`th node, if
+    /// it exists.
+    pub fn ticks(&self, idx: usize) -> Option<Ticks> {
+        self.nodes[..self.num_nodes].get(idx).map(|node| node.ticks)
+    }
+
+    /// Sets the ticks on the segment which starts at the `idx`th node, if it
+    /// exists.
+    pub fn set_ticks(&mut self, idx: usize, ticks: Ticks) {
+        if let Some(node) = self.nodes[..self.num_nodes].get_mut(idx) {
+            node.ticks = ticks;
+        }
+    }
+
+    /// Sets the number of steps in a grid of ticks around the shape, or
+    /// `None` to use each segment's own number of ticks.
+    pub fn set_tick_grid(&mut self, grid: Option<u32>) {
+        self.tick_grid = grid;
+    }
+
+    /// Sets the seed of the trigger probabilities, which restart from it.
+    pub fn set_trigger_seed(&mut self, seed: u32) {
+        self.trigger_seed = seed;
+        self.trigger_rng.reseed(seed as u64);
+    }
+
+    /// Sets whether fill is active, for fill trigger conditions.
+    pub fn set_fill(&mut self, fill: bool) {
+        self.fill = fill;
+    }
+
+    /// Resets the sequence — the position of nodes and its progress, which
+    /// restarts from the start of the master clock.
     ///
     /// Does not affect the number of nodes.
     pub fn reset(&mut self) {
-        self.progress = 0.0;
         self.emplace_nodes();
+        self.straighten_segments();
         self.update_segments();
         self.update_length();
+
+        self.master_bars = 0.0;
+        self.clock = self.timing.loops_at(0.0);
+        self.trigger_rng.reseed(self.trigger_seed as u64);
+        self.trig_history.reset();
+        self.reset_sensors();
+        self.reset_drift();
+        self.reset_physics();
+        self.reset_balls();
+        self.reset_radial();
+        for playhead in &mut self.playheads {
+            playhead.reset(self.nodes[0].pos, self.clock);
+        }
     }
 
     /// Updates the positions and lengths of each segment.
@@ -133,6 +558,8 @@ impl Sequence {
             self.segments[i].end = end.pos;
             self.segments[i].calculate_distance();
         }
+
+        self.update_crossings();
     }
 
     /// Updates the length of the entire shape.
@@ -145,43 +572,111 @@ impl Sequence {
             .sum();
     }
 
-    fn update_progress(&mut self, delta_time: f32) {
-        let time_per_bar = 60.0 / self.tempo * 4.0;
-        let time_increment = time_per_bar.recip() * delta_time;
+    fn update_progress(&mut self) {
+        self.clock = self.timing.loops_at(self.master_bars);
+        self.update_balls();
+        self.update_radial();
+
+        // the playheads keep moving while another engine plays the shape, but
+        // are silent
+        let playheads_enabled = self.ball_settings.engine == Engine::Playheads;
+
+        for playhead in &mut self.playheads {
+            playhead.advance(
+                self.clock,
+                &self.segments[..self.num_nodes],
+                &self.groove,
+                &self.graph,
+            );
+
+            if !playheads_enabled {
+                continue;
+            }
 
-        self.progress += time_increment;
+            let transpose = playhead.settings().transpose;
+            let secs_per_loop = self.secs_per_bar / self.timing.rate() as f32;
 
-        if self.progress > 1.0 {
-            self.progress -= 1.0;
-        }
-    }
+            for hit in playhead.hits() {
+                let node = &mut self.nodes[hit.vertex];
+                if node.is_rest {
+                    continue;
+                }
+
+                let context = self.trig_history.context(
+                    hit.vertex,
+                    self.num_nodes,
+                    hit.loop_idx,
+                    self.fill,
+                );
+                let played =
+                    node.trigger.should_play(context, &mut self.trigger_rng);
+                self.trig_history.record(hit.vertex, &node.trigger, played);
+
+                if !played {
+                    continue;
+                }
 
-    fn update_progress_node(&mut self) {
-        let progress = self.length * self.progress;
-        let mut idx = 0;
-        let mut lower_len = 0.0;
-        let mut upper_len = 0.0;
+                let segment_secs = playhead
+                    .next_segment_loops(hit, &self.segments[..self.num_nodes])
+                    as f32
+                    * secs_per_loop;
 
-        for i in 0..self.num_nodes {
-            lower_len = upper_len;
-            upper_len += self.segments[i].length;
+                node.tap();
+
+                for (delay, velocity) in
+                    node.ratchet.notes(segment_secs, self.secs_per_bar)
+                {
+                    let mut note_data = node.note_data();
+                    note_data.note += transpose;
+                    note_data.velocity *= velocity;
+                    note_data.delay = delay;
+
+                    self.note_data_sender
+                        .send(note_data)
+                        .expect("failed to send note data");
+                }
+            }
 
-            if (lower_len..=upper_len).contains(&progress) {
-                idx = i;
-                break;
+            let segments = &self.segments[..self.num_nodes];
+
+            for sweep in playhead.sweeps() {
+                let node = &self.nodes[sweep.segment];
+                let points = tick_points(
+                    segments,
+                    sweep.segment,
+                    &node.ticks,
+                    self.tick_grid,
+                );
+
+                for (idx, interp) in points {
+                    if !sweep.crosses(interp) {
+                        continue;
+                    }
+
+                    let offset = node.ticks.offset(idx);
+                    let mut note_data = node.note_data();
+                    note_data.note += offset.pitch + transpose;
+                    note_data.velocity *= offset.velocity;
+
+                    self.note_data_sender
+                        .send(note_data)
+                        .expect("failed to send note data");
+                }
             }
         }
 
-        let interp = ilerp(lower_len, upper_len, progress);
-        let index = |i| i % self.num_nodes;
+        if !playheads_enabled {
+            return;
+        }
 
-        self.progress_node = self.nodes[index(idx)]
-            .pos
-            .lerp(self.nodes[index(idx + 1)].pos, interp);
+        for idx in 0..self.playheads.len() {
+            let transpose = self.playheads[idx].settings().transpose;
 
-        if idx != self.last_behind_idx {
-            self.last_behind_idx = idx;
-            self.tap();
+            for s in 0..self.playheads[idx].sweeps().len() {
+                let sweep = self.playheads[idx].sweeps()[s];
+                self.trigger_sensors(idx, &sweep, transpose);
+                self.trigger_crossings(&sweep, transpose);
+            }
         }
     }
 
@@ -191,47 +686,60 @@ impl Sequence {
         }
     }
 
-    fn tap(&mut self) {
-        self.nodes[self.last_behind_idx].tap();
-        self.note_data_sender
-            .send(self.nodes[self.last_behind_idx].note_data())
-            .expect("failed to send note data");
+    /// Returns the average position of the shape's vertices.
+    fn centre(&self) -> Vec2 {
+        let nodes = &self.nodes[..self.num_nodes];
+        nodes.iter().fold(Vec2::ZERO, |sum, node| sum + node.pos)
+            / nodes.len() as f32
     }
 
-    fn find_lower_node_idx(&self) -> Option<usize> {
-        let pos = self.length * self.progress;
-        let mut lower_bound = 0.0;
-        let mut upper_bound = 0.0;
+    /// Returns the point which is `length` along the shape's perimeter from
+    /// the first vertex.
+    fn point_at_length(&self, length: f32) -> Vec2 {
+        let mut remaining = length.rem_euclid(self.length.max(f32::EPSILON));
 
-        for i in 0..self.num_nodes {
-            upper_bound += self.segments[i].length;
-
-            if lower_bound <= pos && pos < upper_bound {
-                return Some(i);
+        for segment in self.segments.iter().take(self.num_nodes) {
+            if remaining <= segment.length {
+                let interp = ilerp(0.0, segment.length, remaining);
+                return segment.point_at(interp);
             }
 
-            lower_bound = upper_bound;
+            remaining -= segment.length;
         }
 
-        None
+        self.nodes[0].pos
     }
 
-    fn emplace_nodes(&mut self) {
-        let delta_angle = TAU / self.num_nodes as f32;
-        let shape_radius = 250.0;
+    /// Restores the default note data, colour and trigger of every node,
+    /// accenting the first vertex which isn't a rest.
+    fn reset_node_data(&mut self) {
+        let first_hit =
+            self.nodes.iter().take(self.num_nodes).position(|n| !n.is_rest);
+
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            node.set_accent(Some(i) == first_hit);
+            node.trigger = Trigger::default();
+            node.ratchet = Ratchet::default();
+            node.ticks = Ticks::default();
+        }
+    }
 
+    fn emplace_nodes(&mut self) {
         for i in 0..self.num_nodes {
-            let idx = self.num_nodes - i;
-            let dlt_angle = idx as f32 * delta_angle + PI * 0.5;
-            let x = shape_radius * dlt_angle.cos();
-            let y = shape_radius * dlt_angle.sin();
-
-            self.nodes[i].pos.x = x;
-            self.nodes[i].pos.y = y;
+            self.nodes[i].pos = regular_polygon_point(i, self.num_nodes);
         }
     }
 }
 
+/// Returns the position of the `idx`th vertex of a regular polygon with
+/// `num_vertices` vertices, starting from the top and moving clockwise.
+fn regular_polygon_point(idx: usize, num_vertices: usize) -> Vec2 {
+    let delta_angle = TAU / num_vertices as f32;
+    let angle = (num_vertices - idx) as f32 * delta_angle + PI * 0.5;
+
+    vec2(angle.cos(), angle.sin()) * SHAPE_RADIUS
+}
+
 impl Drawable for Sequence {
     fn update(&mut self, input_data: &InputData) {
         if !input_data.is_left_clicked {
@@ -239,8 +747,18 @@ impl Drawable for Sequence {
         }
 
         self.update_node_flash(input_data);
+        self.update_sensors(input_data);
 
         'update_nodes: {
+            if self.is_drawing {
+                self.update_stroke(input_data);
+                break 'update_nodes;
+            }
+
+            if self.update_handles(input_data) {
+                break 'update_nodes;
+            }
+
             if let Some(idx) = self.clicked_idx {
                 self.nodes[idx].update(input_data);
                 break 'update_nodes;
@@ -253,30 +771,68 @@ impl Drawable for Sequence {
 
                 if node.is_clicked() {
                     self.clicked_idx = Some(i);
+                    self.selected_idx = i;
                     break;
                 }
             }
         }
 
-        if self.clicked_idx.is_some() {
+        if let Some(idx) = self.clicked_idx {
+            self.snap_node(idx);
+
+            if self.nodes[idx].pos != self.segments[idx].start {
+                self.detach_from_path(idx);
+            }
+
             self.update_segments();
             self.update_length();
         }
+        else {
+            self.snap_guides.clear();
+        }
 
-        self.update_progress(input_data.delta_time);
-        self.update_progress_node();
+        self.update_morph();
+        self.update_drift(input_data);
+        self.update_physics(input_data);
+        self.update_progress();
     }
 
     fn draw(&self, draw: &Draw, frame: &Frame) {
+        self.draw_snap_guides(draw);
+        self.draw_sensors(draw);
+
         for segment in self.segments.iter().take(self.num_nodes) {
             segment.draw(draw);
         }
 
+        self.draw_edges(draw);
+        self.draw_ticks(draw);
+        self.draw_crossings(draw);
+        self.draw_handles(draw);
+        self.draw_stroke(draw);
+
         for node in self.nodes.iter().take(self.num_nodes) {
             node.draw(draw, frame);
         }
 
-        draw.ellipse().color(RED).radius(8.0).xy(self.progress_node);
+        draw.ellipse()
+            .xy(self.nodes[self.selected_node()].pos)
+            .radius(15.0)
+            .no_fill()
+            .stroke_color(GREY)
+            .stroke_weight(2.0);
+
+        if self.ball_settings.engine == Engine::Playheads {
+            for playhead in &self.playheads {
+                draw.ellipse()
+                    .color(playhead.color())
+                    .radius(8.0)
+                    .xy(playhead.pos());
+            }
+        }
+
+        self.draw_balls(draw);
+        self.draw_radial_hand(draw);
     }
 
     fn force_redraw(&self, draw: &Draw, frame: &Frame) {
@@ -0,0 +1,339 @@
+//! Module for morphing, which moves a shape gradually from one stored
+//! snapshot of its vertices to another.
+
+use super::*;
+
+/// The number of snapshots a shape can store.
+pub const MAX_SNAPSHOTS: usize = 4;
+
+/// Settings for morphing between two snapshots.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MorphSettings {
+    /// The index of the snapshot morphed from.
+    pub from: usize,
+    /// The index of the snapshot morphed to.
+    pub to: usize,
+    /// How far the shape is morphed, from `0.0` (`from`) to `1.0` (`to`).
+    pub amount: f32,
+    /// The number of bars the shape takes to morph all the way from one
+    /// snapshot to the other, or `0` to follow `amount` straight away.
+    pub bars: u32,
+}
+
+/// A stored vertex of a shape.
+#[derive(Clone, Copy, Debug)]
+struct Vertex {
+    pos: Vec2,
+    note: f32,
+    is_rest: bool,
+}
+
+/// The vertices of a shape, stored so it can be morphed to or from.
+#[derive(Clone, Debug)]
+pub(super) struct Snapshot {
+    vertices: Vec<Vertex>,
+}
+
+/// Returns how far around `vertices` each vertex is, from `0.0` at the first
+/// vertex, measured along straight lines between them.
+fn perimeter_fractions(vertices: &[Vertex]) -> Vec<f32> {
+    let num = vertices.len();
+    let mut fractions = Vec::with_capacity(num);
+    let mut length = 0.0;
+
+    for i in 0..num {
+        fractions.push(length);
+        length += vertices[i].pos.distance(vertices[(i + 1) % num].pos);
+    }
+
+    // vertices which are all in the same place are spread evenly
+    if length <= f32::EPSILON {
+        return (0..num).map(|i| i as f32 / num as f32).collect();
+    }
+
+    for fraction in &mut fractions {
+        *fraction /= length;
+    }
+
+    fractions
+}
+
+/// Returns the point `fraction` of the way around the straight lines between
+/// `vertices`, where each vertex is at its entry in `fractions`.
+fn point_at_fraction(
+    vertices: &[Vertex],
+    fractions: &[f32],
+    fraction: f32,
+) -> Vec2 {
+    let num = vertices.len();
+    let idx = fractions.iter().rposition(|&f| f <= fraction).unwrap_or(0);
+    let start = fractions[idx];
+    let end = fractions.get(idx + 1).copied().unwrap_or(1.0);
+    let interp = if end > start {
+        (fraction - start) / (end - start)
+    }
+    else {
+        0.0
+    };
+
+    vertices[idx].pos.lerp(vertices[(idx + 1) % num].pos, interp)
+}
+
+/// Spreads the vertices of `small` to match the vertices of `large`, which
+/// has at least as many.
+///
+/// Each vertex of `small` is matched to the vertex of `large` nearest to it
+/// in perimeter fraction, in order, and always the first to the first. The
+/// remaining vertices of `large` get a rest at the same fraction of the way
+/// between the matched vertices either side of them, with the pitch of the
+/// vertex they match so it doesn't glide.
+fn fit_vertices(small: &[Vertex], large: &[Vertex]) -> Vec<Vertex> {
+    let (m, n) = (small.len(), large.len());
+    let small_fractions = perimeter_fractions(small);
+    let large_fractions = perimeter_fractions(large);
+
+    // the index of the vertex of `large` which each vertex of `small` matches
+    let mut matches: Vec<usize> = Vec::with_capacity(m);
+
+    for (k, &fraction) in small_fractions.iter().enumerate() {
+        let lo = matches.last().map_or(0, |&idx| idx + 1);
+        let hi = n - (m - k);
+        let nearest = (lo..=hi)
+            .min_by(|&i, &j| {
+                let dist = |idx: usize| (large_fractions[idx] - fraction).abs();
+                dist(i).total_cmp(&dist(j))
+            })
+            .unwrap_or(lo);
+
+        matches.push(if k == 0 { 0 } else { nearest });
+    }
+
+    let mut fitted = Vec::with_capacity(n);
+
+    for (k, &idx) in matches.iter().enumerate() {
+        fitted.push(small[k]);
+
+        // the vertices of `large` between this match and the next
+        let next = matches.get(k + 1).copied().unwrap_or(n);
+        let small_start = small_fractions[k];
+        let small_end = small_fractions.get(k + 1).copied().unwrap_or(1.0);
+        let large_start = large_fractions[idx];
+        let large_end = large_fractions.get(next).copied().unwrap_or(1.0);
+
+        for (i, vertex) in large.iter().enumerate().take(next).skip(idx + 1) {
+            let interp = if large_end > large_start {
+                (large_fractions[i] - large_start) / (large_end - large_start)
+            }
+            else {
+                0.0
+            };
+            let fraction = lerp(small_start, small_end, interp);
+
+            fitted.push(Vertex {
+                pos: point_at_fraction(small, &small_fractions, fraction),
+                note: vertex.note,
+                is_rest: true,
+            });
+        }
+    }
+
+    fitted
+}
+
+impl Sequence {
+    /// Stores the shape's vertices (their positions, pitches and rests) as
+    /// the `idx`th snapshot, returning its number of vertices.
+    pub fn store_snapshot(&mut self, idx: usize) -> Result<usize, String> {
+        let Some(slot) = self.snapshots.get_mut(idx) else {
+            return Err(format!("There are only {MAX_SNAPSHOTS} snapshots"));
+        };
+
+        let vertices: Vec<Vertex> = self.nodes[..self.num_nodes]
+            .iter()
+            .map(|node| Vertex {
+                pos: node.pos,
+                note: node.note_data().note,
+                is_rest: node.is_rest,
+            })
+            .collect();
+        let num = vertices.len();
+
+        *slot = Some(Snapshot { vertices });
+
+        Ok(num)
+    }
+
+    /// Sets how the shape morphs between two snapshots.
+    ///
+    /// The shape only changes when the morph moves or the snapshots morphed
+    /// between change, so other layouts can be used and stored in between.
+    pub fn set_morph(&mut self, settings: MorphSettings) {
+        let last = self.morph;
+        self.morph = settings;
+
+        if settings.from != last.from || settings.to != last.to {
+            self.morph_changed = true;
+        }
+
+        if settings.bars == 0 && settings.amount != self.morph_amount {
+            self.morph_amount = settings.amount;
+            self.morph_changed = true;
+        }
+    }
+
+    /// Moves the morph towards its amount at the speed set by its number of
+    /// bars, laying the shape out between the two snapshots if it moved.
+    pub(super) fn update_morph(&mut self) {
+        // the master clock can jump backwards, such as when it is reset
+        let bars = (self.master_bars - self.morph_clock).max(0.0);
+        self.morph_clock = self.master_bars;
+
+        let MorphSettings { amount, bars: morph_bars, .. } = self.morph;
+        let delta = amount - self.morph_amount;
+
+        let step = if morph_bars > 0 {
+            (bars / morph_bars as f64) as f32
+        }
+        else {
+            0.0
+        };
+
+        if step > 0.0 && delta != 0.0 {
+            self.morph_amount += delta.clamp(-step, step);
+            self.morph_changed = true;
+        }
+
+        if self.morph_changed {
+            self.morph_changed = false;
+            self.apply_morph();
+        }
+    }
+
+    /// Lays the shape out between the two snapshots, if both are stored.
+    ///
+    /// If the snapshots have different numbers of vertices, the smaller one
+    /// gains rests to match the larger (see [`fit_vertices()`]), which play
+    /// once the morph is more than halfway to the snapshot with the vertices.
+    /// The shape then takes on that many vertices, keeping the settings of
+    /// the ones it already has.
+    fn apply_morph(&mut self) {
+        let MorphSettings { from, to, .. } = self.morph;
+        let (Some(Some(from)), Some(Some(to))) =
+            (self.snapshots.get(from), self.snapshots.get(to))
+        else {
+            return;
+        };
+
+        let (from, to) = if from.vertices.len() < to.vertices.len() {
+            (fit_vertices(&from.vertices, &to.vertices), to.vertices.clone())
+        }
+        else {
+            (from.vertices.clone(), fit_vertices(&to.vertices, &from.vertices))
+        };
+
+        let amount = self.morph_amount.clamp(0.0, 1.0);
+        let vertices: Vec<Vertex> = from
+            .iter()
+            .zip(&to)
+            .map(|(a, b)| Vertex {
+                pos: a.pos.lerp(b.pos, amount),
+                note: match (a.is_rest, b.is_rest) {
+                    (true, false) => b.note,
+                    (false, true) => a.note,
+                    _ => lerp(a.note, b.note, amount),
+                },
+                is_rest: if amount < 0.5 { a.is_rest } else { b.is_rest },
+            })
+            .collect();
+
+        let num = vertices.len().min(NODE_CAPACITY);
+
+        // the segments between the old vertices don't join the new ones
+        if num != self.num_nodes {
+            self.num_nodes = num;
+            self.straighten_segments();
+        }
+
+        for (i, vertex) in vertices[..num].iter().enumerate() {
+            self.move_node(i, vertex.pos);
+
+            let node = &mut self.nodes[i];
+            node.is_rest = vertex.is_rest;
+            node.note_data_mut().note = vertex.note;
+        }
+
+        self.update_segments();
+        self.update_length();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(x: f32, y: f32, note: f32) -> Vertex {
+        Vertex { pos: vec2(x, y), note, is_rest: false }
+    }
+
+    /// A square with sides of length `10.0`.
+    fn square() -> Vec<Vertex> {
+        vec![
+            vertex(0.0, 0.0, 60.0),
+            vertex(10.0, 0.0, 62.0),
+            vertex(10.0, 10.0, 64.0),
+            vertex(0.0, 10.0, 65.0),
+        ]
+    }
+
+    /// An octagon made by adding a vertex halfway along each side of
+    /// [`square()`].
+    fn octagon() -> Vec<Vertex> {
+        let points = [
+            (0.0, 0.0),
+            (5.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 5.0),
+            (10.0, 10.0),
+            (5.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 5.0),
+        ];
+
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| vertex(x, y, 70.0 + i as f32))
+            .collect()
+    }
+
+    #[test]
+    fn measures_vertices_around_the_perimeter() {
+        assert_eq!(perimeter_fractions(&square()), [0.0, 0.25, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn fits_shapes_with_as_many_vertices_unchanged() {
+        let fitted = fit_vertices(&square(), &square());
+
+        assert_eq!(fitted.len(), 4);
+        for (a, b) in fitted.iter().zip(&square()) {
+            assert_eq!((a.pos, a.note, a.is_rest), (b.pos, b.note, false));
+        }
+    }
+
+    #[test]
+    fn fills_extra_vertices_with_rests_along_the_smaller_shape() {
+        let fitted = fit_vertices(&square(), &octagon());
+        assert_eq!(fitted.len(), 8);
+
+        let rests: Vec<bool> = fitted.iter().map(|v| v.is_rest).collect();
+        assert_eq!(rests, [false, true, false, true, false, true, false, true]);
+
+        // each rest lies halfway along a side of the square, with the pitch
+        // of the octagon's vertex it matches so it doesn't glide
+        assert!(fitted[1].pos.distance(vec2(5.0, 0.0)) < 1e-4);
+        assert!(fitted[7].pos.distance(vec2(0.0, 5.0)) < 1e-4);
+        assert_eq!(fitted[1].note, 71.0);
+        assert_eq!(fitted[2].note, 62.0);
+    }
+}
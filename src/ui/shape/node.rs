@@ -1,8 +1,24 @@
 //! Module for individual node objects.
+use super::{ratchet::Ratchet, ticks::Ticks, trigger::Trigger};
 use crate::{audio::voice::NoteEventData, prelude::*};
+use std::marker::PhantomData as PD;
 
 const FLASH_TIME_SECS: f32 = 0.40;
 
+/// The MIDI note of a regular node.
+const DEFAULT_NOTE: f32 = 69.0;
+/// How far an accented node is transposed, in semitones.
+const ACCENT_INTERVAL: f32 = 12.0;
+
+const DEFAULT_COLOR: Rgba = Rgba {
+    color: Rgb { red: 0.0, green: 1.0, blue: 0.0, standard: PD },
+    alpha: 1.0,
+};
+const ACCENT_COLOR: Rgba = Rgba {
+    color: Rgb { red: 1.0, green: 0.0, blue: 1.0, standard: PD },
+    alpha: 1.0,
+};
+
 #[derive(Clone, Debug)]
 pub struct Node {
     bounding_rect: Rect,
@@ -13,6 +29,16 @@ pub struct Node {
     pub color: Rgba,
     radius: f32,
 
+    /// Whether this node is a rest, i.e. a vertex which shapes the path
+    /// but never triggers a note.
+    pub is_rest: bool,
+    /// Decides whether the node plays each time a playhead arrives at it.
+    pub trigger: Trigger,
+    /// Repeats the node's note each time it plays.
+    pub ratchet: Ratchet,
+    /// The ticks on the segment which starts at this node.
+    pub ticks: Ticks,
+
     flash_timer: f32,
     is_hovered: bool,
     is_clicked: bool,
@@ -31,14 +57,19 @@ impl Node {
             pos,
             mouse_offset: None,
 
-            color: Rgba::new(0.0, 1.0, 0.0, 1.0),
+            color: DEFAULT_COLOR,
             radius,
             flash_timer: 0.0,
 
+            is_rest: false,
+            trigger: Trigger::default(),
+            ratchet: Ratchet::default(),
+            ticks: Ticks::default(),
+
             is_hovered: false,
             is_clicked: false,
 
-            note_data: NoteEventData { note: 69.0 },
+            note_data: NoteEventData::new(DEFAULT_NOTE),
         }
     }
 
@@ -64,6 +95,19 @@ impl Node {
     pub fn note_data_mut(&mut self) -> &mut NoteEventData {
         &mut self.note_data
     }
+
+    /// Restores the node's default note and colour, raising it by an octave
+    /// if it is accented.
+    pub fn set_accent(&mut self, accented: bool) {
+        if accented {
+            self.note_data.note = DEFAULT_NOTE + ACCENT_INTERVAL;
+            self.color = ACCENT_COLOR;
+        }
+        else {
+            self.note_data.note = DEFAULT_NOTE;
+            self.color = DEFAULT_COLOR;
+        }
+    }
 }
 
 impl Drawable for Node {
@@ -107,6 +151,17 @@ impl Drawable for Node {
     }
 
     fn draw(&self, draw: &Draw, _: &Frame) {
+        if self.is_rest {
+            draw.ellipse()
+                .xy(self.pos)
+                .radius(self.radius * 0.5)
+                .color(WHITE)
+                .stroke_color(BLACK)
+                .stroke_weight(2.0);
+
+            return;
+        }
+
         let col =
             blend(Rgba::new(1.0, 1.0, 1.0, 1.0), self.color, self.flash_timer);
 
@@ -116,6 +171,24 @@ impl Drawable for Node {
             .color(col)
             .stroke_color(BLACK)
             .stroke_weight(3.0);
+
+        // nodes which don't always play are marked with a dot
+        if self.trigger != Trigger::default() {
+            draw.ellipse()
+                .xy(self.pos)
+                .radius(self.radius * 0.3)
+                .color(BLACK);
+        }
+
+        // ratcheted nodes are marked with an outer ring
+        if self.ratchet.count > 1 {
+            draw.ellipse()
+                .xy(self.pos)
+                .radius(self.radius * 1.5)
+                .no_fill()
+                .stroke_color(BLACK)
+                .stroke_weight(1.5);
+        }
     }
 
     fn rect(&self) -> &Rect {
@@ -0,0 +1,136 @@
+//! Module for laying a sequence out along a circle, ellipse or open arc.
+
+use super::*;
+
+/// The largest radius of a path.
+pub const MAX_PATH_RADIUS: f32 = 300.0;
+
+/// The kind of path which a sequence is laid out along.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathKind {
+    /// Straight segments between points on an ellipse.
+    #[default]
+    Polygon,
+    /// The perimeter of an ellipse.
+    Ellipse,
+    /// Part of the perimeter of an ellipse, which the playhead jumps back
+    /// from at its end.
+    Arc,
+}
+
+impl PathKind {
+    /// Every kind of path, in the order they are shown by the UI.
+    pub const ALL: [Self; 3] = [Self::Polygon, Self::Ellipse, Self::Arc];
+
+    /// Returns the kind of path at `idx` in [`PathKind::ALL`], or `Polygon`
+    /// if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The kind of path's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Polygon => "Poly",
+            Self::Ellipse => "Ellipse",
+            Self::Arc => "Arc",
+        }
+    }
+}
+
+/// Settings for laying a sequence out along a path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathSettings {
+    pub kind: PathKind,
+    /// The number of vertices, which are spaced evenly by angle.
+    pub points: usize,
+    /// The horizontal and vertical radii of the ellipse.
+    pub radii: Vec2,
+    /// The angle of the first vertex clockwise from the top, in degrees.
+    pub start: f32,
+    /// The angle an arc covers, in degrees. Circles and ellipses always cover
+    /// the whole way around.
+    pub span: f32,
+}
+
+/// A part of the perimeter of an ellipse centred on the origin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct EllipseArc {
+    pub radii: Vec2,
+    /// The angle of the start of the arc, in radians anticlockwise from the
+    /// right.
+    pub from: f32,
+    /// The angle of the end of the arc.
+    pub to: f32,
+}
+
+impl EllipseArc {
+    /// Returns the point at `t` (`0.0` to `1.0`) of the way around the arc by
+    /// angle.
+    pub fn point(&self, t: f32) -> Vec2 {
+        let angle = lerp(self.from, self.to, t);
+        vec2(angle.cos(), angle.sin()) * self.radii
+    }
+}
+
+impl Sequence {
+    /// Lays the sequence out along a path, with vertices spaced evenly by
+    /// angle. Every vertex is a hit, apart from the end of an arc, which is a
+    /// rest joined back to the first vertex by a gap.
+    ///
+    /// On an ellipse, vertices spaced evenly by angle are not spaced evenly
+    /// around its perimeter, so the rhythm follows the perimeter's length.
+    pub fn set_path(&mut self, settings: PathSettings) {
+        let PathSettings { kind, points, radii, start, span } = settings;
+
+        let is_arc = kind == PathKind::Arc;
+        let points = points.clamp(MIN_NUM_VERTICES, NODE_CAPACITY - 1);
+        let span = if is_arc { span.to_radians() } else { TAU };
+
+        // clockwise from the top
+        let angle = |i: usize| {
+            PI * 0.5 - start.to_radians() - span * i as f32 / points as f32
+        };
+
+        let num = if is_arc { points + 1 } else { points };
+        let vertices: Vec<Vec2> = (0..num)
+            .map(|i| vec2(angle(i).cos(), angle(i).sin()) * radii)
+            .collect();
+        let rests: Vec<bool> = (0..num).map(|i| i == points).collect();
+
+        self.set_vertices(&vertices, &rests);
+
+        if kind != PathKind::Polygon {
+            for i in 0..points {
+                let (from, to) = (angle(i), angle(i + 1));
+                let arc = EllipseArc { radii, from, to };
+                self.segments[i].curve.arc = Some(arc);
+            }
+        }
+
+        if is_arc {
+            self.segments[points].curve.is_gap = true;
+        }
+
+        self.update_segments();
+        self.update_length();
+    }
+
+    /// Makes the segments either side of the `idx`th node follow their own
+    /// curve rather than a path, so they follow the node as it is dragged.
+    pub(super) fn detach_from_path(&mut self, idx: usize) {
+        let prev_idx = (idx + self.num_nodes - 1) % self.num_nodes;
+
+        self.segments[prev_idx].curve.arc = None;
+        self.segments[idx].curve.arc = None;
+    }
+
+    /// Moves the node at `idx` to `pos`, detaching it from its path if it
+    /// moves, as a path can't be kept through a vertex which has left it.
+    pub(super) fn move_node(&mut self, idx: usize, pos: Vec2) {
+        if self.nodes[idx].pos != pos {
+            self.nodes[idx].pos = pos;
+            self.detach_from_path(idx);
+        }
+    }
+}
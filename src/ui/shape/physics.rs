@@ -0,0 +1,275 @@
+//! Module for physics, which moves the vertices of a shape as masses joined by
+//! springs along its segments.
+
+use super::*;
+
+/// The length of each physics step, in seconds. The simulation is always
+/// advanced in steps of this length, so it behaves the same at any frame
+/// rate.
+const PHYSICS_STEP: f32 = 1.0 / 240.0;
+/// The most steps taken in one update, so that a long frame doesn't stall
+/// the app.
+const MAX_STEPS_PER_UPDATE: usize = 32;
+/// The proportion of a vertex's speed kept when it bounces off an edge.
+const RESTITUTION: f32 = 0.5;
+
+/// Settings for the physics simulation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhysicsSettings {
+    pub enabled: bool,
+    /// The stiffness of the springs, as the acceleration per pixel of
+    /// stretch, in pixels per second squared.
+    pub stiffness: f32,
+    /// The downwards acceleration of every vertex, in pixels per second
+    /// squared.
+    pub gravity: f32,
+    /// The proportion (`0.0` to `1.0`) of a vertex's speed lost each second.
+    pub damping: f32,
+}
+
+/// The state of the physics simulation.
+#[derive(Clone, Debug)]
+pub(super) struct Springs {
+    pub settings: PhysicsSettings,
+    /// The velocity of each vertex, in pixels per second.
+    velocities: Vec<Vec2>,
+    /// The length each segment's spring pulls it towards.
+    rest_lengths: Vec<f32>,
+    /// The force on each vertex, kept between steps to avoid allocating.
+    forces: Vec<Vec2>,
+    /// The time passed which is too short for another step.
+    remainder: f32,
+    /// The position of the dragged vertex at the last update.
+    grab_pos: Option<Vec2>,
+}
+
+impl Default for Springs {
+    fn default() -> Self {
+        Self {
+            settings: PhysicsSettings::default(),
+            velocities: vec![Vec2::ZERO; NODE_CAPACITY],
+            rest_lengths: vec![0.0; NODE_CAPACITY],
+            forces: Vec::with_capacity(NODE_CAPACITY),
+            remainder: 0.0,
+            grab_pos: None,
+        }
+    }
+}
+
+impl Springs {
+    /// Advances `positions` by one step. The vertex at `pinned` (if any) is
+    /// held where it is, though it still pulls on its neighbours.
+    fn step(
+        &mut self,
+        positions: &mut [Vec2],
+        pinned: Option<usize>,
+        rect: Rect,
+    ) {
+        let PhysicsSettings { stiffness, gravity, damping, .. } = self.settings;
+        let num = positions.len();
+        let forces = &mut self.forces;
+        forces.clear();
+        forces.resize(num, vec2(0.0, -gravity));
+
+        for i in 0..num {
+            let j = (i + 1) % num;
+            let delta = positions[j] - positions[i];
+            let dist = delta.length();
+
+            if dist <= f32::EPSILON {
+                continue;
+            }
+
+            let force =
+                delta / dist * (dist - self.rest_lengths[i]) * stiffness;
+            forces[i] += force;
+            forces[j] -= force;
+        }
+
+        let decay = (1.0 - damping.clamp(0.0, 0.99)).powf(PHYSICS_STEP);
+        let (min, max) = (rect.bottom_left(), rect.top_right());
+
+        for (i, pos) in positions.iter_mut().enumerate() {
+            if pinned == Some(i) {
+                continue;
+            }
+
+            let velocity = &mut self.velocities[i];
+            *velocity = (*velocity + forces[i] * PHYSICS_STEP) * decay;
+            *pos += *velocity * PHYSICS_STEP;
+
+            // bounce off the edges of the rect
+            for axis in 0..2 {
+                if pos[axis] < min[axis] || pos[axis] > max[axis] {
+                    pos[axis] = pos[axis].clamp(min[axis], max[axis]);
+                    velocity[axis] *= -RESTITUTION;
+                }
+            }
+        }
+    }
+}
+
+impl Sequence {
+    /// Sets up the physics simulation. Turning it on makes the current
+    /// segment lengths the ones the springs pull towards.
+    pub fn set_physics(&mut self, settings: PhysicsSettings) {
+        let enabled = !self.springs.settings.enabled && settings.enabled;
+        self.springs.settings = settings;
+
+        if enabled {
+            self.reset_physics();
+        }
+    }
+
+    /// Stops every vertex, and makes the current segment lengths the ones
+    /// the springs pull towards.
+    pub(super) fn reset_physics(&mut self) {
+        let num = self.num_nodes;
+
+        for i in 0..num {
+            let end = self.nodes[(i + 1) % num].pos;
+            self.springs.rest_lengths[i] = self.nodes[i].pos.distance(end);
+        }
+
+        self.springs.velocities.fill(Vec2::ZERO);
+        self.springs.remainder = 0.0;
+    }
+
+    /// Advances the physics simulation by the time since the last frame, in
+    /// fixed steps. A dragged vertex follows the mouse, and is flung with the
+    /// mouse's speed when released.
+    pub(super) fn update_physics(&mut self, input_data: &InputData) {
+        if !self.springs.settings.enabled {
+            return;
+        }
+
+        let dt = input_data.delta_time;
+        let grabbed = self.clicked_idx.map(|idx| (idx, self.nodes[idx].pos));
+
+        if let (Some((idx, pos)), Some(last)) = (grabbed, self.springs.grab_pos)
+        {
+            if dt > 0.0 {
+                self.springs.velocities[idx] = (pos - last) / dt;
+            }
+        }
+        self.springs.grab_pos = grabbed.map(|(_, pos)| pos);
+
+        // any time beyond the most steps per update is dropped
+        let remainder = self.springs.remainder + dt;
+        let steps =
+            ((remainder / PHYSICS_STEP) as usize).min(MAX_STEPS_PER_UPDATE);
+        self.springs.remainder = (remainder - steps as f32 * PHYSICS_STEP)
+            .clamp(0.0, PHYSICS_STEP);
+
+        self.step_physics(steps);
+    }
+
+    /// Advances the physics simulation by `steps` steps of [`PHYSICS_STEP`]
+    /// seconds. The same steps from the same state always give the same
+    /// result.
+    pub fn step_physics(&mut self, steps: usize) {
+        if steps == 0 {
+            return;
+        }
+
+        let num = self.num_nodes;
+        let positions = &mut self.spring_positions;
+        positions.clear();
+        positions.extend(self.nodes[..num].iter().map(|node| node.pos));
+
+        for _ in 0..steps {
+            self.springs.step(positions, self.clicked_idx, self.rect);
+        }
+
+        for i in 0..num {
+            self.move_node(i, self.spring_positions[i]);
+        }
+
+        self.update_segments();
+        self.update_length();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns springs joining the vertices of a triangle with sides of
+    /// length `100.0`.
+    fn springs(gravity: f32) -> Springs {
+        let mut springs = Springs {
+            settings: PhysicsSettings {
+                enabled: true,
+                stiffness: 40.0,
+                gravity,
+                damping: 0.5,
+            },
+            ..Springs::default()
+        };
+        springs.rest_lengths[..3].fill(100.0);
+        springs
+    }
+
+    /// A triangle stretched to nearly twice the size its springs pull it
+    /// towards.
+    fn stretched() -> Vec<Vec2> {
+        vec![vec2(0.0, 100.0), vec2(86.6, -50.0), vec2(-86.6, -50.0)]
+    }
+
+    fn run(springs: &mut Springs, positions: &mut [Vec2], steps: usize) {
+        let rect = Rect::from_w_h(1000.0, 1000.0);
+
+        for _ in 0..steps {
+            springs.step(positions, None, rect);
+        }
+    }
+
+    #[test]
+    fn the_same_steps_give_the_same_result() {
+        let (mut a, mut b) = (stretched(), stretched());
+
+        run(&mut springs(200.0), &mut a, 500);
+        run(&mut springs(200.0), &mut b, 500);
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn settles_at_the_rest_lengths() {
+        let mut springs = springs(0.0);
+        let mut positions = stretched();
+
+        run(&mut springs, &mut positions, 240 * 20);
+
+        for i in 0..3 {
+            let length = positions[i].distance(positions[(i + 1) % 3]);
+            assert!((length - 100.0).abs() < 0.5, "side {i} is {length}");
+            assert!(springs.velocities[i].length() < 0.5);
+        }
+    }
+
+    #[test]
+    fn gravity_pulls_the_shape_down_to_the_floor() {
+        let mut springs = springs(200.0);
+        let mut positions = stretched();
+
+        run(&mut springs, &mut positions, 240 * 20);
+
+        let floor = -500.0;
+        let lowest = positions.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+        assert!(lowest - floor < 1.0, "the lowest vertex is at {lowest}");
+    }
+
+    #[test]
+    fn a_pinned_vertex_stays_put() {
+        let mut springs = springs(200.0);
+        let mut positions = stretched();
+        let rect = Rect::from_w_h(1000.0, 1000.0);
+
+        for _ in 0..100 {
+            springs.step(&mut positions, Some(0), rect);
+        }
+
+        assert_eq!(positions[0], stretched()[0]);
+    }
+}
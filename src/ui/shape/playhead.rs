@@ -0,0 +1,844 @@
+//! Module for the playheads which travel around a shape.
+
+use super::*;
+
+/// The largest number of vertices a random walk or graph walk may arrive at
+/// in one frame.
+const MAX_WALK_STEPS_PER_FRAME: usize = 64;
+
+/// The largest number of playheads on one shape.
+pub const MAX_PLAYHEADS: usize = 6;
+/// The colour of each playhead, in the order they are added.
+const PLAYHEAD_COLORS: [Srgb<u8>; MAX_PLAYHEADS] =
+    [RED, BLUE, DARKORANGE, PURPLE, TEAL, SADDLEBROWN];
+
+/// The settings which make each playhead on a shape different.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayheadSettings {
+    /// How far ahead of the shared clock the playhead is, in loops (`0.0` to
+    /// `1.0`).
+    pub phase: f32,
+    /// The playhead's speed relative to the shared clock, as `(a, b)`: the
+    /// playhead travels `a` loops for every `b` loops of the clock.
+    pub ratio: (u32, u32),
+    /// How far the notes the playhead plays are transposed, in semitones.
+    pub transpose: f32,
+}
+
+impl Default for PlayheadSettings {
+    fn default() -> Self {
+        Self { phase: 0.0, ratio: (1, 1), transpose: 0.0 }
+    }
+}
+
+impl PlayheadSettings {
+    /// The playhead's speed relative to the shared clock.
+    pub fn rate(&self) -> f64 {
+        self.ratio.0.max(1) as f64 / self.ratio.1.max(1) as f64
+    }
+}
+
+/// The direction in which a playhead travels around its shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayDirection {
+    /// Around the shape from the first vertex, in order.
+    #[default]
+    Forward,
+    /// Around the shape from the first vertex, in reverse order.
+    Reverse,
+    /// From the first vertex to the last and back again, without using the
+    /// segment which closes the shape. Each pass takes one loop.
+    PingPong,
+    /// Like `PingPong`, but the playhead waits at each end vertex for as long
+    /// as the closing segment would take, and plays it again as it leaves.
+    PingPongRepeat,
+    /// From each vertex to a random neighbour.
+    RandomWalk,
+    /// From each vertex along one of the edges which leave it, chosen by the
+    /// shape's graph rule.
+    Graph,
+}
+
+impl PlayDirection {
+    /// Every direction, in the order they are shown by the UI.
+    pub const ALL: [Self; 6] = [
+        Self::Forward,
+        Self::Reverse,
+        Self::PingPong,
+        Self::PingPongRepeat,
+        Self::RandomWalk,
+        Self::Graph,
+    ];
+
+    /// Returns the direction at `idx` in [`PlayDirection::ALL`], or `Forward`
+    /// if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The direction's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Forward => "Fwd",
+            Self::Reverse => "Rev",
+            Self::PingPong => "P-P",
+            Self::PingPongRepeat => "P-P+",
+            Self::RandomWalk => "Walk",
+            Self::Graph => "Graph",
+        }
+    }
+}
+
+/// The part of a segment which a playhead moved along in one frame, as
+/// interpolation values between the segment's start (`0.0`) and end (`1.0`).
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Sweep {
+    pub segment: usize,
+    pub from: f32,
+    pub to: f32,
+}
+
+impl Sweep {
+    /// Returns the vertex which the sweep arrived at, if any.
+    fn arrival(&self, num_segments: usize) -> Option<usize> {
+        if self.to > self.from && self.to >= 1.0 {
+            Some((self.segment + 1) % num_segments)
+        }
+        else if self.to < self.from && self.to <= 0.0 {
+            Some(self.segment)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Returns whether the sweep crossed the point `interp` of the way along
+    /// its segment. The point where it arrived counts, but the point where it
+    /// started doesn't, so no point is crossed twice by consecutive sweeps.
+    pub fn crosses(&self, interp: f32) -> bool {
+        if self.to > self.from {
+            self.from < interp && interp <= self.to
+        }
+        else {
+            self.to <= interp && interp < self.from
+        }
+    }
+}
+
+/// A vertex which a playhead arrived at.
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub vertex: usize,
+    /// The loop the playhead was in, counting from `0`. Each loop ends as the
+    /// playhead arrives back at its first vertex.
+    pub loop_idx: i64,
+}
+
+/// The state of a random walk or graph walk: the vertex it left, the vertex
+/// it is heading to, and how far between them it is.
+#[derive(Clone, Copy, Debug, Default)]
+struct Walk {
+    from: usize,
+    to: usize,
+    progress: f32,
+}
+
+/// A playhead, which travels along the segments of a shape and reports each
+/// vertex it arrives at.
+///
+/// The playhead follows a clock shared by every playhead on the shape, and
+/// works out the whole path it covered each frame, so vertices are never
+/// skipped however far it moves in one frame.
+#[derive(Clone, Debug)]
+pub struct Playhead {
+    /// The time travelled, in loops.
+    time: f64,
+    settings: PlayheadSettings,
+    direction: PlayDirection,
+    walk: Walk,
+    /// The number of times each vertex has been left by a graph walk, which
+    /// picks its edge when cycling through them.
+    turns: Vec<usize>,
+    rng: Rng,
+    /// The seed of the random walk.
+    seed: u64,
+
+    /// The position of the playhead.
+    pos: Vec2,
+    color: Srgb<u8>,
+    /// The parts of each segment travelled in the last frame.
+    sweeps: Vec<Sweep>,
+    /// The vertices arrived at in the last frame.
+    hits: Vec<Hit>,
+}
+
+impl Playhead {
+    /// Creates the `idx`th `Playhead` of a shape, at `start`.
+    pub fn new(idx: usize, start: Vec2) -> Self {
+        let seed = idx as u64;
+
+        Self {
+            time: 0.0,
+            settings: PlayheadSettings::default(),
+            direction: PlayDirection::Forward,
+            walk: Walk::default(),
+            turns: vec![0; NODE_CAPACITY],
+            rng: Rng::new(seed),
+            seed,
+
+            pos: start,
+            color: PLAYHEAD_COLORS[idx % MAX_PLAYHEADS],
+            sweeps: Vec::with_capacity(NODE_CAPACITY),
+            hits: Vec::with_capacity(NODE_CAPACITY),
+        }
+    }
+
+    /// Moves the playhead to where it should be at `clock` loops of the
+    /// shared clock, drawn at `start`, and restarts any random walk.
+    pub fn reset(&mut self, start: Vec2, clock: f64) {
+        self.time = self.time_at(clock);
+        self.walk = Walk::default();
+        self.turns.fill(0);
+        self.rng.reseed(self.seed);
+
+        self.pos = start;
+        self.sweeps.clear();
+        self.hits.clear();
+    }
+
+    /// Returns the playhead's settings.
+    pub fn settings(&self) -> PlayheadSettings {
+        self.settings
+    }
+
+    /// Sets the playhead's settings, moving it straight to where they put it
+    /// at `clock` loops of the shared clock (without playing anything in
+    /// between).
+    pub fn set_settings(&mut self, settings: PlayheadSettings, clock: f64) {
+        self.settings = settings;
+        self.time = self.time_at(clock);
+    }
+
+    /// Sets the direction the playhead travels in.
+    pub fn set_direction(&mut self, direction: PlayDirection) {
+        if direction != self.direction {
+            self.direction = direction;
+            self.walk = Walk::default();
+            self.turns.fill(0);
+        }
+    }
+
+    /// Returns the position of the playhead.
+    pub fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    /// Returns the colour of the playhead.
+    pub fn color(&self) -> Srgb<u8> {
+        self.color
+    }
+
+    /// Returns the vertices the playhead arrived at in the last frame.
+    pub fn hits(&self) -> &[Hit] {
+        &self.hits
+    }
+
+    /// Returns the parts of each segment travelled in the last frame.
+    pub(super) fn sweeps(&self) -> &[Sweep] {
+        &self.sweeps
+    }
+
+    /// Returns how long the playhead takes to travel from `hit` to the next
+    /// vertex, in loops of the shared clock. Groove is ignored.
+    pub(super) fn next_segment_loops(
+        &self,
+        hit: &Hit,
+        segments: &[Segment],
+    ) -> f64 {
+        let num = segments.len();
+        let total: f64 = segments.iter().map(|s| s.length as f64).sum();
+        if num == 0 || total <= f64::EPSILON {
+            return 0.0;
+        }
+
+        let Walk { from, to, .. } = self.walk;
+        if self.direction == PlayDirection::Graph
+            && from == hit.vertex
+            && to < num
+        {
+            let length = Route::new(from, to, segments).length(segments);
+            return length as f64 / total / self.settings.rate();
+        }
+
+        let backwards = match self.direction {
+            PlayDirection::Forward => false,
+            PlayDirection::Reverse => true,
+            PlayDirection::PingPong | PlayDirection::PingPongRepeat => {
+                // the playhead turns around at each end vertex
+                let is_odd_pass = hit.loop_idx.rem_euclid(2) == 1;
+                hit.vertex != 0 && (hit.vertex == num - 1 || is_odd_pass)
+            }
+            PlayDirection::RandomWalk => {
+                from == hit.vertex && to != (hit.vertex + 1) % num
+            }
+            PlayDirection::Graph => false,
+        };
+        let segment = if backwards {
+            (hit.vertex + num - 1) % num
+        }
+        else {
+            hit.vertex
+        };
+
+        // a ping-pong pass doesn't use the closing segment
+        let path = if self.direction == PlayDirection::PingPong {
+            total - segments[num - 1].length as f64
+        }
+        else {
+            total
+        };
+
+        segments[segment].length as f64 / path.max(f64::EPSILON)
+            / self.settings.rate()
+    }
+
+    /// Moves the playhead along `segments` to where it should be at `clock`
+    /// loops of the shared clock, where `groove` warps its speed through each
+    /// loop (or pass, for ping-pong). `graph` holds the edges a graph walk
+    /// may take.
+    pub(super) fn advance(
+        &mut self,
+        clock: f64,
+        segments: &[Segment],
+        groove: &Groove,
+        graph: &Graph,
+    ) {
+        self.sweeps.clear();
+        self.hits.clear();
+
+        let num = segments.len();
+        let total: f64 = segments.iter().map(|s| s.length as f64).sum();
+        if num == 0 || total <= f64::EPSILON {
+            return;
+        }
+
+        let (start, end) = (self.time, self.time_at(clock).max(self.time));
+        self.time = end;
+
+        // each loop is travelled separately, so every hit knows which loop
+        // it belongs to
+        let mut time = start;
+
+        while time < end {
+            let loop_start = time.floor();
+            let loop_end = end.min(loop_start + 1.0);
+            let loop_idx = loop_start as i64;
+            let first_hit = self.hits.len();
+
+            // the distance travelled through this loop
+            let distance = |time: f64| {
+                let warped = groove.warp((time - loop_start) as f32);
+                total * (loop_start + warped as f64)
+            };
+
+            match self.direction {
+                PlayDirection::Forward => {
+                    self.travel(
+                        segments, total, distance(time), distance(loop_end),
+                    );
+                }
+                PlayDirection::Reverse => {
+                    self.travel(
+                        segments, total, -distance(time), -distance(loop_end),
+                    );
+                }
+                PlayDirection::PingPong | PlayDirection::PingPongRepeat => {
+                    self.ping_pong(
+                        time, loop_end, loop_start, segments, total, groove,
+                    );
+                }
+                PlayDirection::RandomWalk => {
+                    let walked = distance(loop_end) - distance(time);
+                    self.random_walk(walked as f32, segments);
+                }
+                PlayDirection::Graph => {
+                    let walked = distance(loop_end) - distance(time);
+                    self.graph_walk(walked as f32, segments, graph);
+                }
+            }
+
+            for hit in &mut self.hits[first_hit..] {
+                hit.loop_idx = loop_idx;
+            }
+
+            // when repeating, the end vertex plays again as the playhead
+            // leaves it at the start of the next pass
+            let is_forward = loop_idx.rem_euclid(2) == 0;
+            if self.direction == PlayDirection::PingPongRepeat
+                && loop_end >= loop_start + 1.0
+            {
+                self.hits.push(Hit {
+                    vertex: if is_forward { num - 1 } else { 0 },
+                    loop_idx: loop_idx + 1,
+                });
+            }
+
+            time = loop_end;
+        }
+
+        // edges across the shape have no sweeps, so a graph walk is placed
+        // by its progress along its edge
+        let Walk { from, to, progress } = self.walk;
+        if self.direction == PlayDirection::Graph && from < num && to < num {
+            let route = Route::new(from, to, segments);
+            self.pos = route.point_at(progress, segments);
+        }
+        else if let Some(sweep) = self.sweeps.last() {
+            let segment = &segments[sweep.segment];
+            self.pos = segment.point_at(sweep.to);
+        }
+    }
+
+    /// Returns the time of the playhead at `clock` loops of the shared clock.
+    fn time_at(&self, clock: f64) -> f64 {
+        clock.mul_add(self.settings.rate(), self.settings.phase as f64)
+    }
+
+    /// Moves the playhead along the path around `segments` from distance
+    /// `from` to distance `to`, recording each vertex it arrives at.
+    fn travel(&mut self, segments: &[Segment], total: f64, from: f64, to: f64) {
+        let first = self.sweeps.len();
+        push_sweeps(segments, total, from, to, &mut self.sweeps);
+
+        let num = segments.len();
+        self.hits.extend(
+            self.sweeps[first..]
+                .iter()
+                .filter_map(|s| s.arrival(num))
+                .map(|vertex| Hit { vertex, loop_idx: 0 }),
+        );
+    }
+
+    /// Moves the playhead from `start` to `end` through the pass which
+    /// started at `pass`. Even passes travel from the first vertex to the last,
+    /// and odd passes travel back, along the shape without its closing
+    /// segment.
+    fn ping_pong(
+        &mut self,
+        start: f64,
+        end: f64,
+        pass: f64,
+        segments: &[Segment],
+        total: f64,
+        groove: &Groove,
+    ) {
+        let repeat = self.direction == PlayDirection::PingPongRepeat;
+        let open = total - segments[segments.len() - 1].length as f64;
+        let is_forward = (pass as i64).rem_euclid(2) == 0;
+
+        let along = |time: f64| {
+            let warped = groove.warp((time - pass) as f32) as f64;
+
+            // when repeating, the playhead waits at the end for as long as
+            // the closing segment would take
+            let d = if repeat {
+                (warped * total).min(open)
+            }
+            else {
+                warped * open
+            };
+
+            if is_forward { d } else { open - d }
+        };
+
+        self.travel(segments, total, along(start), along(end));
+    }
+
+    /// Moves the playhead `distance` along a random walk, choosing a random
+    /// neighbour each time it arrives at a vertex.
+    fn random_walk(&mut self, mut distance: f32, segments: &[Segment]) {
+        let num = segments.len();
+
+        let Walk { from, to, .. } = self.walk;
+        let is_neighbour = to == (from + 1) % num || from == (to + 1) % num;
+        if from >= num || to >= num || !is_neighbour {
+            self.walk = Walk {
+                from: 0,
+                to: self.random_neighbour(0, num),
+                progress: 0.0,
+            };
+        }
+
+        for _ in 0..MAX_WALK_STEPS_PER_FRAME {
+            if distance <= 0.0 {
+                return;
+            }
+
+            let Walk { from, to, progress } = self.walk;
+            let is_forward = to == (from + 1) % num;
+            let segment = if is_forward { from } else { to };
+            let interp = |p: f32| if is_forward { p } else { 1.0 - p };
+
+            let len = segments[segment].length;
+            let remaining = (1.0 - progress) * len;
+
+            if distance < remaining {
+                let progress_next = progress + distance / len;
+
+                self.sweeps.push(Sweep {
+                    segment,
+                    from: interp(progress),
+                    to: interp(progress_next),
+                });
+                self.walk.progress = progress_next;
+
+                return;
+            }
+
+            self.sweeps.push(Sweep {
+                segment,
+                from: interp(progress),
+                to: interp(1.0),
+            });
+            self.hits.push(Hit { vertex: to, loop_idx: 0 });
+
+            distance -= remaining;
+            self.walk = Walk {
+                from: to,
+                to: self.random_neighbour(to, num),
+                progress: 0.0,
+            };
+        }
+    }
+
+    /// Moves the playhead `distance` along a graph walk, choosing one of the
+    /// edges which leave each vertex it arrives at. Travelling along an edge
+    /// across the shape takes as long as a segment of the same length, but
+    /// only edges along segments are swept.
+    fn graph_walk(
+        &mut self,
+        mut distance: f32,
+        segments: &[Segment],
+        graph: &Graph,
+    ) {
+        let num = segments.len();
+
+        let Walk { from, to, .. } = self.walk;
+        if !graph.has_edge(from, to, num) {
+            self.walk = Walk {
+                from: 0,
+                to: self.next_vertex(0, graph, num),
+                progress: 0.0,
+            };
+        }
+
+        for _ in 0..MAX_WALK_STEPS_PER_FRAME {
+            if distance <= 0.0 {
+                return;
+            }
+
+            let Walk { from, to, progress } = self.walk;
+            let route = Route::new(from, to, segments);
+            let len = route.length(segments);
+            let remaining = (1.0 - progress) * len;
+
+            if distance < remaining {
+                let progress_next = progress + distance / len;
+
+                self.push_route_sweep(route, progress, progress_next);
+                self.walk.progress = progress_next;
+
+                return;
+            }
+
+            self.push_route_sweep(route, progress, 1.0);
+            self.hits.push(Hit { vertex: to, loop_idx: 0 });
+
+            distance -= remaining;
+            self.walk = Walk {
+                from: to,
+                to: self.next_vertex(to, graph, num),
+                progress: 0.0,
+            };
+        }
+    }
+
+    /// Records the part of `route` travelled from `from` to `to`, if it runs
+    /// along a segment.
+    fn push_route_sweep(&mut self, route: Route, from: f32, to: f32) {
+        if let Route::Segment { segment, is_forward } = route {
+            let interp = |p: f32| if is_forward { p } else { 1.0 - p };
+
+            self.sweeps.push(Sweep {
+                segment,
+                from: interp(from),
+                to: interp(to),
+            });
+        }
+    }
+
+    /// Chooses the vertex a graph walk heads to from `vertex`, following the
+    /// graph's rule.
+    fn next_vertex(
+        &mut self,
+        vertex: usize,
+        graph: &Graph,
+        num: usize,
+    ) -> usize {
+        let edges = graph.outgoing(vertex, num);
+
+        let edge = match graph.rule {
+            GraphRule::Weighted => {
+                let total: f32 = edges.clone().map(|edge| edge.weight).sum();
+                let mut target = self.rng.range(0.0, total);
+
+                edges
+                    .clone()
+                    .find(|edge| {
+                        target -= edge.weight;
+                        target < 0.0
+                    })
+                    .or_else(|| edges.last())
+            }
+            GraphRule::Cycle => {
+                let turn = self.turns[vertex];
+                self.turns[vertex] = turn.wrapping_add(1);
+
+                edges.clone().nth(turn % edges.count())
+            }
+        };
+
+        edge.expect("there is always an outgoing edge").to
+    }
+
+    fn random_neighbour(&mut self, vertex: usize, num: usize) -> usize {
+        if self.rng.chance(0.5) {
+            (vertex + 1) % num
+        }
+        else {
+            (vertex + num - 1) % num
+        }
+    }
+}
+
+/// Appends the sweeps which cover the path around `segments` from distance
+/// `from` to distance `to`, where both distances may be outside of the shape's
+/// `total` length (to represent multiple loops) and `to` may be below `from`
+/// (to travel backwards).
+fn push_sweeps(
+    segments: &[Segment],
+    total: f64,
+    mut from: f64,
+    to: f64,
+    sweeps: &mut Vec<Sweep>,
+) {
+    let num = segments.len();
+    let is_forward = to > from;
+
+    while (is_forward && from < to) || (!is_forward && from > to) {
+        // travelling backwards, the end of a loop is treated as part of the
+        // loop before it, and the start as part of the loop after it
+        let loop_start = if is_forward {
+            (from / total).floor() * total
+        }
+        else {
+            ((from / total).ceil() - 1.0) * total
+        };
+        let local = from - loop_start;
+
+        let mut seg_start = 0.0;
+        let Some(idx) = segments.iter().position(|s| {
+            let len = s.length as f64;
+            let seg_end = seg_start + len;
+            let contains = len > 0.0
+                && if is_forward { local < seg_end } else { local <= seg_end };
+
+            if !contains {
+                seg_start = seg_end;
+            }
+
+            contains
+        })
+        else {
+            return;
+        };
+
+        let len = segments[idx].length as f64;
+        let abs_start = loop_start + seg_start;
+        let boundary = if is_forward { abs_start + len } else { abs_start };
+        let reached =
+            if is_forward { to >= boundary } else { to <= boundary };
+
+        let interp = |d: f64| ((d - abs_start) / len).clamp(0.0, 1.0) as f32;
+
+        sweeps.push(Sweep {
+            segment: idx,
+            from: interp(from),
+            to: if reached { interp(boundary).round() } else { interp(to) },
+        });
+
+        if !reached {
+            return;
+        }
+
+        // vertices at the same position as the one just reached are passed
+        // at the same time
+        for step in 1..num {
+            let next = if is_forward {
+                (idx + step) % num
+            }
+            else {
+                (idx + num - step) % num
+            };
+
+            if segments[next].length > 0.0 {
+                break;
+            }
+
+            let (from, to) = if is_forward { (0.0, 1.0) } else { (1.0, 0.0) };
+            sweeps.push(Sweep { segment: next, from, to });
+        }
+
+        from = boundary;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the segments of a square with sides of length `100.0`.
+    fn square() -> Vec<Segment> {
+        let corners = [
+            vec2(0.0, 0.0),
+            vec2(100.0, 0.0),
+            vec2(100.0, 100.0),
+            vec2(0.0, 100.0),
+        ];
+
+        (0..4)
+            .map(|i| {
+                let mut segment = Segment {
+                    start: corners[i],
+                    end: corners[(i + 1) % 4],
+                    ..Segment::default()
+                };
+                segment.calculate_distance();
+                segment
+            })
+            .collect()
+    }
+
+    /// Advances `playhead` around `segments` to `clock`, returning each vertex
+    /// it arrived at with its loop.
+    fn advance(
+        playhead: &mut Playhead,
+        clock: f64,
+        segments: &[Segment],
+    ) -> Vec<(usize, i64)> {
+        let (groove, graph) = (Groove::straight(), Graph::default());
+        playhead.advance(clock, segments, &groove, &graph);
+
+        playhead.hits().iter().map(|hit| (hit.vertex, hit.loop_idx)).collect()
+    }
+
+    #[test]
+    fn a_frame_ending_on_a_vertex_hits_it_once() {
+        let segments = square();
+        let mut playhead = Playhead::new(0, segments[0].start);
+
+        assert_eq!(advance(&mut playhead, 0.25, &segments), [(1, 0)]);
+        assert_eq!(playhead.pos(), segments[1].start);
+        assert_eq!(advance(&mut playhead, 0.4, &segments), []);
+        assert_eq!(advance(&mut playhead, 0.5, &segments), [(2, 0)]);
+
+        let hit = playhead.hits()[0];
+        assert_eq!(playhead.next_segment_loops(&hit, &segments), 0.25);
+    }
+
+    #[test]
+    fn a_frame_past_the_end_of_a_loop_hits_both_loops() {
+        let segments = square();
+        let mut playhead = Playhead::new(0, segments[0].start);
+
+        assert_eq!(
+            advance(&mut playhead, 0.9, &segments),
+            [(1, 0), (2, 0), (3, 0)]
+        );
+        assert_eq!(advance(&mut playhead, 1.3, &segments), [(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn phase_moves_the_playhead_ahead_of_the_clock() {
+        let segments = square();
+        let mut playhead = Playhead::new(0, segments[0].start);
+        let settings = PlayheadSettings { phase: 0.25, ..Default::default() };
+        playhead.set_settings(settings, 0.0);
+
+        // the vertex the playhead starts on isn't hit
+        assert_eq!(advance(&mut playhead, 0.5, &segments), [(2, 0), (3, 0)]);
+        assert_eq!(advance(&mut playhead, 0.75, &segments), [(0, 0)]);
+    }
+
+    /// Plays `playhead` around `segments` for `loops` loops in frames of
+    /// `frame` loops, returning every vertex it arrived at.
+    fn play(
+        playhead: &mut Playhead,
+        loops: f64,
+        frame: f64,
+        segments: &[Segment],
+    ) -> Vec<usize> {
+        let frames = (loops / frame).round() as usize;
+
+        (1..=frames)
+            .flat_map(|i| advance(playhead, i as f64 * frame, segments))
+            .map(|(vertex, _)| vertex)
+            .collect()
+    }
+
+    #[test]
+    fn ping_pong_plays_the_end_vertices_once_per_turn() {
+        let segments = square();
+
+        // frames of an eighth of a loop end exactly on the turnaround
+        for frame in [0.125, 0.01] {
+            let mut playhead = Playhead::new(0, segments[0].start);
+            playhead.set_direction(PlayDirection::PingPong);
+
+            assert_eq!(
+                play(&mut playhead, 4.0, frame, &segments),
+                [1, 2, 3, 2, 1, 0, 1, 2, 3, 2, 1, 0],
+                "{frame}"
+            );
+        }
+    }
+
+    #[test]
+    fn random_walks_repeat_with_the_same_seed() {
+        let segments = square();
+        let walk = |idx: usize| {
+            let mut playhead = Playhead::new(idx, segments[0].start);
+            playhead.set_direction(PlayDirection::RandomWalk);
+            play(&mut playhead, 8.0, 0.05, &segments)
+        };
+
+        let vertices = walk(1);
+        assert_eq!(vertices.len(), 32);
+        assert_eq!(vertices, walk(1));
+        assert_ne!(vertices, walk(2));
+
+        // every step is to a neighbour of the vertex before
+        for pair in vertices.windows(2) {
+            let gap = (pair[1] + 4 - pair[0]) % 4;
+            assert!(gap == 1 || gap == 3, "{pair:?}");
+        }
+
+        // resetting restarts the walk from its seed
+        let mut playhead = Playhead::new(1, segments[0].start);
+        playhead.set_direction(PlayDirection::RandomWalk);
+        play(&mut playhead, 3.0, 0.05, &segments);
+        playhead.reset(segments[0].start, 0.0);
+
+        assert_eq!(play(&mut playhead, 8.0, 0.05, &segments), vertices);
+    }
+}
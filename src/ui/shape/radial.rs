@@ -0,0 +1,130 @@
+//! Module for the radial clock, whose hand turns around the middle of a shape
+//! at a constant speed and plays each vertex as it points at it.
+//!
+//! The time of each vertex depends only on its angle around the middle of
+//! the shape, not on the length of the segments between vertices, so a
+//! vertex can be moved towards or away from the middle without changing the
+//! rhythm.
+
+use super::*;
+
+/// The distance the hand reaches past the furthest vertex.
+const HAND_OVERHANG: f32 = 20.0;
+
+impl Sequence {
+    /// Moves the middle of the radial clock to the middle of the shape, and
+    /// starts its hand from the shape's clock.
+    ///
+    /// The middle only moves when the layout changes, so dragging a vertex
+    /// doesn't change the angles of the others.
+    pub(super) fn reset_radial(&mut self) {
+        self.radial_centre = self.centre();
+        self.radial_clock = self.clock;
+    }
+
+    /// Returns how far around the radial clock (`0.0` to `1.0`) the
+    /// `idx`th vertex is, clockwise from the top.
+    fn radial_turn(&self, idx: usize) -> f64 {
+        let offset = self.nodes[idx].pos - self.radial_centre;
+        let angle = PI * 0.5 - offset.y.atan2(offset.x);
+
+        (angle / TAU).rem_euclid(1.0) as f64
+    }
+
+    /// Returns how many turns the radial clock's hand has made at `loops`
+    /// loops of the shape's clock. The groove warps its speed through each
+    /// turn, as it does the playheads'.
+    fn hand_turns(&self, loops: f64) -> f64 {
+        let loop_idx = loops.floor();
+        loop_idx + self.groove.warp((loops - loop_idx) as f32) as f64
+    }
+
+    /// Moves the radial clock's hand to the shape's clock, playing each
+    /// vertex it passes. The hand turns once per loop.
+    pub(super) fn update_radial(&mut self) {
+        let from = self.hand_turns(self.radial_clock);
+        let to = self.hand_turns(self.clock);
+        self.radial_clock = self.clock;
+
+        if self.ball_settings.engine != Engine::Radial || to <= from {
+            return;
+        }
+
+        let num = self.num_nodes;
+        let turns: Vec<f64> = (0..num).map(|i| self.radial_turn(i)).collect();
+        let secs_per_loop = self.secs_per_bar / self.timing.rate() as f32;
+
+        for (i, &turn) in turns.iter().enumerate() {
+            // the loop in which the hand last pointed at the vertex
+            let loop_idx = (to - turn).floor();
+            if loop_idx <= (from - turn).floor() {
+                continue;
+            }
+
+            let node = &mut self.nodes[i];
+            if node.is_rest {
+                continue;
+            }
+
+            let context =
+                self.trig_history.context(i, num, loop_idx as i64, self.fill);
+            let played =
+                node.trigger.should_play(context, &mut self.trigger_rng);
+            self.trig_history.record(i, &node.trigger, played);
+
+            if !played {
+                continue;
+            }
+
+            // the time until the hand points at the next vertex, ignoring
+            // the groove
+            let gap = turns
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &other)| (other - turn).rem_euclid(1.0))
+                .filter(|&gap| gap > 0.0)
+                .fold(1.0, f64::min);
+            let segment_secs = gap as f32 * secs_per_loop;
+
+            node.tap();
+
+            for (delay, velocity) in
+                node.ratchet.notes(segment_secs, self.secs_per_bar)
+            {
+                let mut note_data = node.note_data();
+                note_data.velocity *= velocity;
+                note_data.delay = delay;
+
+                self.note_data_sender
+                    .send(note_data)
+                    .expect("failed to send note data");
+            }
+        }
+    }
+
+    /// Draws the radial clock's hand, if it plays the shape.
+    pub(super) fn draw_radial_hand(&self, draw: &Draw) {
+        if self.ball_settings.engine != Engine::Radial {
+            return;
+        }
+
+        let centre = self.radial_centre;
+        let reach = self.nodes[..self.num_nodes]
+            .iter()
+            .map(|node| node.pos.distance(centre))
+            .fold(0.0, f32::max)
+            + HAND_OVERHANG;
+
+        let turn = self.hand_turns(self.clock).rem_euclid(1.0) as f32;
+        let angle = PI * 0.5 - turn * TAU;
+        let tip = centre + vec2(angle.cos(), angle.sin()) * reach;
+
+        draw.line()
+            .start(centre)
+            .end(tip)
+            .color(BLACK)
+            .weight(2.0);
+        draw.ellipse().xy(centre).radius(4.0).color(BLACK);
+    }
+}
@@ -0,0 +1,223 @@
+//! Module for generating random shapes from a seed.
+
+use super::*;
+
+/// The number of shapes tried before giving up on finding one whose segments
+/// are all long enough.
+const MAX_ATTEMPTS: usize = 200;
+/// The smallest distance of a non-convex shape's vertices from its centre,
+/// relative to the radius of the regular polygons.
+const MIN_RADIUS: f32 = 0.25;
+/// The smallest amount a convex shape is squashed by along each axis.
+const MIN_STRETCH: f32 = 0.6;
+
+/// Settings for generating a random shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RandomSettings {
+    /// The seed of the shape, which always produces the same shape with the
+    /// same settings.
+    pub seed: u32,
+    /// The number of vertices.
+    pub points: usize,
+    /// Whether the shape is convex.
+    pub convex: bool,
+    /// The shortest length of any segment, in pixels.
+    pub min_length: f32,
+    /// Whether the segments last whole numbers of steps of the snap grid.
+    pub snap: bool,
+}
+
+/// Returns `num` random vertices around the centre of the sequencer, in
+/// clockwise order.
+///
+/// Convex shapes are polygons inscribed in a circle which is then squashed,
+/// and non-convex shapes have their vertices at random distances from the
+/// centre. As the vertices are sorted by angle, neither kind crosses itself.
+fn random_vertices(rng: &mut Rng, num: usize, convex: bool) -> Vec<Vec2> {
+    let mut angles: Vec<f32> = (0..num).map(|_| rng.range(0.0, TAU)).collect();
+    angles.sort_unstable_by(|a, b| b.total_cmp(a));
+
+    let stretch = if convex {
+        vec2(rng.range(MIN_STRETCH, 1.0), rng.range(MIN_STRETCH, 1.0))
+    }
+    else {
+        Vec2::ONE
+    };
+
+    angles
+        .into_iter()
+        .map(|angle| {
+            let radius = if convex { 1.0 } else { rng.range(MIN_RADIUS, 1.0) };
+            vec2(angle.cos(), angle.sin()) * stretch * radius * SHAPE_RADIUS
+        })
+        .collect()
+}
+
+/// Rounds `lengths` to whole numbers of steps which add up to `divisions`,
+/// with at least one step each, keeping them as close to their original
+/// proportions as possible.
+///
+/// Returns `None` if there are more lengths than steps.
+fn quantise_lengths(lengths: &[f32], divisions: usize) -> Option<Vec<f32>> {
+    if lengths.len() > divisions {
+        return None;
+    }
+
+    let total: f32 = lengths.iter().sum();
+    let ideal: Vec<f32> = lengths
+        .iter()
+        .map(|len| len / total * divisions as f32)
+        .collect();
+    let mut steps: Vec<usize> =
+        ideal.iter().map(|&x| (x.floor() as usize).max(1)).collect();
+
+    // the lengths which were rounded down the most gain steps first, and
+    // those which were rounded up the most lose them first
+    let error = |steps: &[usize], i: usize| ideal[i] - steps[i] as f32;
+
+    while steps.iter().sum::<usize>() < divisions {
+        let i = (0..steps.len())
+            .max_by(|&a, &b| error(&steps, a).total_cmp(&error(&steps, b)))?;
+        steps[i] += 1;
+    }
+
+    while steps.iter().sum::<usize>() > divisions {
+        let i = (0..steps.len())
+            .filter(|&i| steps[i] > 1)
+            .min_by(|&a, &b| error(&steps, a).total_cmp(&error(&steps, b)))?;
+        steps[i] -= 1;
+    }
+
+    Some(steps.into_iter().map(|s| s as f32).collect())
+}
+
+impl Sequence {
+    /// Lays the sequence out as a random shape generated from `settings`.
+    ///
+    /// Shapes are generated until one has no segment shorter than the
+    /// minimum length. If snapping is on, each shape is bent so that its
+    /// segments last whole numbers of steps of the snap grid before it is
+    /// checked.
+    pub fn set_random(
+        &mut self,
+        settings: RandomSettings,
+    ) -> Result<(), String> {
+        let RandomSettings { seed, points, convex, min_length, snap } =
+            settings;
+        let num = points.clamp(MIN_NUM_VERTICES, NODE_CAPACITY);
+        let divisions = self.snap_grid.divisions().filter(|_| snap);
+
+        if divisions.is_some_and(|divisions| num > divisions) {
+            return Err(format!(
+                "A {} grid is too coarse for {num} vertices",
+                self.snap_grid.name()
+            ));
+        }
+
+        let mut rng = Rng::new(seed as u64);
+
+        for _ in 0..MAX_ATTEMPTS {
+            let vertices = random_vertices(&mut rng, num, convex);
+            let long_enough = (0..num).all(|i| {
+                vertices[i].distance(vertices[(i + 1) % num]) >= min_length
+            });
+
+            if !long_enough {
+                continue;
+            }
+
+            self.set_vertices(&vertices, &[]);
+
+            let Some(divisions) = divisions else {
+                return Ok(());
+            };
+
+            let lengths: Vec<f32> =
+                self.segments[..num].iter().map(|s| s.length).collect();
+            let steps = quantise_lengths(&lengths, divisions)
+                .ok_or("The shape could not be snapped to the grid")?;
+
+            self.set_durations(&steps, SolveOptions { convex, centred: true })?;
+
+            // snapping moves the vertices, which may bring some closer together
+            if self.segments[..self.num_nodes]
+                .iter()
+                .all(|s| s.length >= min_length)
+            {
+                return Ok(());
+            }
+        }
+
+        Err(format!(
+            "No shape with {num} vertices {min_length:.0} px apart was found"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_shape() {
+        for convex in [false, true] {
+            let shape = |seed| random_vertices(&mut Rng::new(seed), 6, convex);
+
+            assert_eq!(shape(7), shape(7));
+            assert_ne!(shape(7), shape(8));
+        }
+    }
+
+    #[test]
+    fn vertices_go_clockwise_so_shapes_never_cross_themselves() {
+        for seed in 0..20 {
+            let vertices = random_vertices(&mut Rng::new(seed), 8, false);
+            let angles: Vec<f32> = vertices
+                .iter()
+                .map(|v| v.y.atan2(v.x).rem_euclid(TAU))
+                .collect();
+
+            assert!(angles.windows(2).all(|pair| pair[0] >= pair[1]));
+        }
+    }
+
+    #[test]
+    fn quantises_lengths_to_whole_steps_in_proportion() {
+        assert_eq!(
+            quantise_lengths(&[1.0, 1.0, 2.0], 8),
+            Some(vec![2.0, 2.0, 4.0])
+        );
+
+        let steps = quantise_lengths(&[0.01, 10.0, 10.0], 8).unwrap();
+        assert_eq!(steps.iter().sum::<f32>(), 8.0);
+        assert!(steps.iter().all(|&s| s >= 1.0));
+
+        assert_eq!(quantise_lengths(&[1.0; 5], 4), None);
+    }
+
+    #[test]
+    fn snapped_shapes_last_whole_steps_and_keep_their_spacing() {
+        let rect = Rect::from_w_h(1000.0, 1000.0);
+        let mut seq = Sequence::new(rect, mpsc::channel().0, 4);
+        seq.set_snap_grid(SnapGrid::Sixteenth);
+
+        for (seed, convex) in [(1, true), (2, true), (3, false), (4, false)] {
+            let settings = RandomSettings {
+                seed,
+                points: 5,
+                convex,
+                min_length: 80.0,
+                snap: true,
+            };
+            seq.set_random(settings).expect("a shape is found");
+
+            let step = seq.length / 16.0;
+            for segment in &seq.segments[..seq.num_nodes()] {
+                let steps = segment.length / step;
+
+                assert!(segment.length >= 80.0, "{} px", segment.length);
+                assert!((steps - steps.round()).abs() < 0.05, "{steps} steps");
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Module for ratchets, which repeat a node's note in a quick burst.
+
+use super::*;
+
+/// The largest number of notes in a ratchet.
+pub const MAX_RATCHET_COUNT: u32 = 8;
+
+/// The time between each note of a ratchet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RatchetSpacing {
+    /// A fixed note value: `1 / division` of a bar, or two thirds of that if
+    /// `triplet` is set.
+    Note { division: u32, triplet: bool },
+    /// The time the playhead takes to reach the next vertex, divided evenly
+    /// between the notes.
+    #[default]
+    Segment,
+}
+
+impl RatchetSpacing {
+    /// Every spacing, in the order they are shown by the UI.
+    pub const ALL: [Self; 8] = [
+        Self::Segment,
+        Self::Note { division: 8, triplet: false },
+        Self::Note { division: 8, triplet: true },
+        Self::Note { division: 16, triplet: false },
+        Self::Note { division: 16, triplet: true },
+        Self::Note { division: 32, triplet: false },
+        Self::Note { division: 32, triplet: true },
+        Self::Note { division: 64, triplet: false },
+    ];
+
+    /// Returns the spacing at `idx` in [`RatchetSpacing::ALL`], or `Segment`
+    /// if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Returns the index of the spacing in [`RatchetSpacing::ALL`].
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|s| *s == self).unwrap_or(0)
+    }
+
+    /// The spacing's short display name.
+    pub fn name(self) -> String {
+        match self {
+            Self::Note { division, triplet } => {
+                format!("1/{division}{}", if triplet { "T" } else { "" })
+            }
+            Self::Segment => "Seg".to_string(),
+        }
+    }
+}
+
+/// Repeats a node's note in a quick burst each time it plays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ratchet {
+    /// The number of notes played, including the first (`1` to
+    /// [`MAX_RATCHET_COUNT`]).
+    pub count: u32,
+    pub spacing: RatchetSpacing,
+    /// How the velocity changes through the burst (`-1.0` to `1.0`).
+    /// Positive values start quieter and rise to full velocity, and negative
+    /// values start at full velocity and fall.
+    pub ramp: f32,
+}
+
+impl Default for Ratchet {
+    fn default() -> Self {
+        Self { count: 1, spacing: RatchetSpacing::Segment, ramp: 0.0 }
+    }
+}
+
+impl Ratchet {
+    /// Returns the delay (in seconds) and velocity scale of each note of the
+    /// ratchet, where `segment_secs` is the time the playhead takes to reach
+    /// the next vertex and `secs_per_bar` is the length of a bar.
+    pub fn notes(
+        &self,
+        segment_secs: f32,
+        secs_per_bar: f32,
+    ) -> impl Iterator<Item = (f32, f32)> {
+        let count = self.count.clamp(1, MAX_RATCHET_COUNT);
+        let ramp = self.ramp.clamp(-1.0, 1.0);
+
+        let spacing = match self.spacing {
+            RatchetSpacing::Note { division, triplet } => {
+                let secs = secs_per_bar / division.max(1) as f32;
+                if triplet { secs * 2.0 / 3.0 } else { secs }
+            }
+            RatchetSpacing::Segment => segment_secs / count as f32,
+        };
+
+        (0..count).map(move |i| {
+            let t = i as f32 / (count - 1).max(1) as f32;
+            let scale = if count == 1 {
+                1.0
+            }
+            else if ramp >= 0.0 {
+                ramp.mul_add(t - 1.0, 1.0)
+            }
+            else {
+                ramp.mul_add(t, 1.0)
+            };
+
+            (spacing * i as f32, scale)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratchet(count: u32, spacing: RatchetSpacing, ramp: f32) -> Ratchet {
+        Ratchet { count, spacing, ramp }
+    }
+
+    fn delays(ratchet: Ratchet) -> Vec<f32> {
+        ratchet.notes(1.0, 2.0).map(|(delay, _)| delay).collect()
+    }
+
+    fn velocities(ratchet: Ratchet) -> Vec<f32> {
+        ratchet.notes(1.0, 2.0).map(|(_, velocity)| velocity).collect()
+    }
+
+    #[test]
+    fn plays_the_clamped_number_of_notes() {
+        let segment = RatchetSpacing::Segment;
+
+        assert_eq!(Ratchet::default().notes(1.0, 2.0).count(), 1);
+        assert_eq!(ratchet(0, segment, 0.0).notes(1.0, 2.0).count(), 1);
+        assert_eq!(ratchet(5, segment, 0.0).notes(1.0, 2.0).count(), 5);
+        assert_eq!(
+            ratchet(20, segment, 0.0).notes(1.0, 2.0).count(),
+            MAX_RATCHET_COUNT as usize
+        );
+    }
+
+    #[test]
+    fn spaces_notes_by_segment_or_note_value() {
+        let segment = ratchet(4, RatchetSpacing::Segment, 0.0);
+        assert_eq!(delays(segment), [0.0, 0.25, 0.5, 0.75]);
+
+        // a sixteenth of a two second bar
+        let note = RatchetSpacing::Note { division: 16, triplet: false };
+        assert_eq!(delays(ratchet(3, note, 0.0)), [0.0, 0.125, 0.25]);
+
+        let triplet = RatchetSpacing::Note { division: 16, triplet: true };
+        let triplet_delays = delays(ratchet(3, triplet, 0.0));
+        assert!(epsilon_eq(triplet_delays[1], 0.125 * 2.0 / 3.0));
+        assert!(epsilon_eq(triplet_delays[2], 0.25 * 2.0 / 3.0));
+    }
+
+    #[test]
+    fn ramps_velocity_through_the_burst() {
+        let segment = RatchetSpacing::Segment;
+
+        assert_eq!(velocities(ratchet(3, segment, 0.0)), [1.0, 1.0, 1.0]);
+        assert_eq!(velocities(ratchet(3, segment, 1.0)), [0.0, 0.5, 1.0]);
+        assert_eq!(velocities(ratchet(3, segment, -0.5)), [1.0, 0.75, 0.5]);
+        // ramps are clamped
+        assert_eq!(velocities(ratchet(2, segment, 4.0)), [0.0, 1.0]);
+        // a single note is never ramped
+        assert_eq!(velocities(ratchet(1, segment, 1.0)), [1.0]);
+    }
+}
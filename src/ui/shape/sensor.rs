@@ -0,0 +1,187 @@
+//! Module for sensors, which are free-floating trigger points that play a
+//! note when a playhead passes close to them.
+
+use super::*;
+
+/// The largest number of sensors on one shape.
+pub const MAX_SENSORS: usize = 16;
+/// The default distance within which a playhead triggers a sensor.
+pub const DEFAULT_SENSOR_RADIUS: f32 = 40.0;
+/// The default MIDI note played by sensors.
+pub const DEFAULT_SENSOR_NOTE: f32 = 81.0;
+/// The radius of the point drawn at each sensor.
+const SENSOR_POINT_RADIUS: f32 = 5.0;
+const FLASH_TIME_SECS: f32 = 0.40;
+
+/// A free-floating trigger point.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Sensor {
+    pos: Vec2,
+    /// Whether the sensor can be triggered by each playhead, which is cleared
+    /// once it triggers and set again when the playhead moves out of range.
+    armed: [bool; MAX_PLAYHEADS],
+    flash_timer: f32,
+}
+
+impl Sensor {
+    fn new(pos: Vec2) -> Self {
+        Self { pos, armed: [true; MAX_PLAYHEADS], flash_timer: 0.0 }
+    }
+
+    /// Checks whether the `playhead`th playhead passed within `radius` of the
+    /// sensor while moving in a straight line from `from` to `to`, returning
+    /// the velocity of the note it should play if so.
+    ///
+    /// The sensor triggers at the playhead's closest approach, so that the
+    /// velocity reflects how close it passed.
+    fn detect(
+        &mut self,
+        playhead: usize,
+        from: Vec2,
+        to: Vec2,
+        radius: f32,
+    ) -> Option<f32> {
+        let path = to - from;
+        let len_sq = path.length_squared();
+        if len_sq <= f32::EPSILON || playhead >= MAX_PLAYHEADS {
+            return None;
+        }
+
+        let interp = ((self.pos - from).dot(path) / len_sq).clamp(0.0, 1.0);
+        let distance = self.pos.distance(from.lerp(to, interp));
+
+        // if the closest point is the end of the path, the playhead is still
+        // getting closer
+        let mut velocity = None;
+        if self.armed[playhead] && interp < 1.0 && distance <= radius {
+            self.armed[playhead] = false;
+            self.flash_timer = 1.0;
+            velocity = Some(1.0 - distance / radius.max(f32::EPSILON));
+        }
+
+        if self.pos.distance(to) > radius {
+            self.armed[playhead] = true;
+        }
+
+        velocity
+    }
+}
+
+impl Sequence {
+    /// Adds a sensor at `pos`, or removes the sensor under `pos` if there is
+    /// one. Sensors may only be placed inside the shape's rect.
+    fn toggle_sensor(&mut self, pos: Vec2) {
+        if let Some(idx) = self
+            .sensors
+            .iter()
+            .position(|s| s.pos.distance(pos) <= SENSOR_POINT_RADIUS * 2.0)
+        {
+            self.sensors.remove(idx);
+        }
+        else if self.rect.contains(pos) && self.sensors.len() < MAX_SENSORS {
+            self.sensors.push(Sensor::new(pos));
+        }
+    }
+
+    /// Adds or removes sensors with the right mouse button, and fades their
+    /// flashes.
+    pub(super) fn update_sensors(&mut self, input_data: &InputData) {
+        if input_data.right_button_just_clicked() {
+            self.toggle_sensor(input_data.mouse_pos);
+        }
+
+        for sensor in &mut self.sensors {
+            sensor.flash_timer = f32::max(
+                sensor.flash_timer - input_data.delta_time / FLASH_TIME_SECS,
+                0.0,
+            );
+        }
+    }
+
+    /// Plays a note for each sensor which the `idx`th playhead passed close
+    /// to along `sweep`.
+    pub(super) fn trigger_sensors(
+        &mut self,
+        idx: usize,
+        sweep: &Sweep,
+        transpose: f32,
+    ) {
+        let segment = self.segments[sweep.segment];
+        if self.sensors.is_empty() || segment.curve.is_gap {
+            return;
+        }
+
+        // curved segments are followed in short, straight steps
+        let mut points = segment.points_between(sweep.from, sweep.to);
+        let Some(mut from) = points.next() else {
+            return;
+        };
+
+        for to in points {
+            for sensor in &mut self.sensors {
+                let Some(velocity) =
+                    sensor.detect(idx, from, to, self.sensor_radius)
+                else {
+                    continue;
+                };
+
+                let mut note_data = NoteEventData::new(self.sensor_note);
+                note_data.note += transpose;
+                note_data.velocity = velocity;
+
+                self.note_data_sender
+                    .send(note_data)
+                    .expect("failed to send note data");
+            }
+
+            from = to;
+        }
+    }
+
+    /// Removes every sensor.
+    pub fn clear_sensors(&mut self) {
+        self.sensors.clear();
+    }
+
+    /// Returns the number of sensors on the shape.
+    pub fn num_sensors(&self) -> usize {
+        self.sensors.len()
+    }
+
+    /// Sets the distance within which a playhead triggers a sensor.
+    pub fn set_sensor_radius(&mut self, radius: f32) {
+        self.sensor_radius = radius;
+    }
+
+    /// Sets the MIDI note played by sensors.
+    pub fn set_sensor_note(&mut self, note: f32) {
+        self.sensor_note = note;
+    }
+
+    /// Re-arms every sensor, such as when the playheads are moved back to the
+    /// start.
+    pub(super) fn reset_sensors(&mut self) {
+        for sensor in &mut self.sensors {
+            sensor.armed = [true; MAX_PLAYHEADS];
+        }
+    }
+
+    /// Draws each sensor and the range within which it is triggered.
+    pub(super) fn draw_sensors(&self, draw: &Draw) {
+        for sensor in &self.sensors {
+            let alpha = sensor.flash_timer.mul_add(0.15, 0.05);
+
+            draw.ellipse()
+                .xy(sensor.pos)
+                .radius(self.sensor_radius)
+                .color(Rgba::new(0.0, 0.0, 0.0, alpha))
+                .stroke_color(Rgba::new(0.0, 0.0, 0.0, 0.3))
+                .stroke_weight(1.0);
+
+            draw.ellipse()
+                .xy(sensor.pos)
+                .radius(SENSOR_POINT_RADIUS)
+                .color(BLACK);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Module for locking dragged nodes to a musical grid.
+
+use super::*;
+
+/// A musical grid which dragged nodes can be locked to, so that the segments
+/// either side of a dragged node always last a whole number of grid steps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SnapGrid {
+    #[default]
+    Off,
+    Quarter,
+    Eighth,
+    Sixteenth,
+    ThirtySecond,
+    QuarterTriplet,
+    EighthTriplet,
+    SixteenthTriplet,
+}
+
+impl SnapGrid {
+    /// Every grid, in the order they are shown by the UI.
+    pub const ALL: [Self; 8] = [
+        Self::Off,
+        Self::Quarter,
+        Self::Eighth,
+        Self::Sixteenth,
+        Self::ThirtySecond,
+        Self::QuarterTriplet,
+        Self::EighthTriplet,
+        Self::SixteenthTriplet,
+    ];
+
+    /// Returns the grid at `idx` in [`SnapGrid::ALL`], or `Off` if `idx` is
+    /// out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// The number of grid steps per bar (i.e. per loop of the shape), or
+    /// `None` if snapping is disabled.
+    pub fn divisions(self) -> Option<usize> {
+        match self {
+            Self::Off => None,
+            Self::Quarter => Some(4),
+            Self::Eighth => Some(8),
+            Self::Sixteenth => Some(16),
+            Self::ThirtySecond => Some(32),
+            Self::QuarterTriplet => Some(6),
+            Self::EighthTriplet => Some(12),
+            Self::SixteenthTriplet => Some(24),
+        }
+    }
+
+    /// The grid's short display name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Quarter => "1/4",
+            Self::Eighth => "1/8",
+            Self::Sixteenth => "1/16",
+            Self::ThirtySecond => "1/32",
+            Self::QuarterTriplet => "1/4T",
+            Self::EighthTriplet => "1/8T",
+            Self::SixteenthTriplet => "1/16T",
+        }
+    }
+}
+
+impl Sequence {
+    /// Sets the grid which dragged nodes are locked to.
+    pub fn set_snap_grid(&mut self, grid: SnapGrid) {
+        self.snap_grid = grid;
+    }
+
+    /// Moves the node at `idx` to the valid grid position nearest to it, and
+    /// stores every valid position so they can be drawn as guides.
+    pub(super) fn snap_node(&mut self, idx: usize) {
+        self.snap_guides.clear();
+
+        let Some(divisions) = self.snap_grid.divisions() else {
+            return;
+        };
+
+        self.find_snap_positions(idx, divisions);
+
+        let pos = self.nodes[idx].pos;
+        let nearest = self.snap_guides.iter().copied().min_by(|a, b| {
+            a.distance_squared(pos).total_cmp(&b.distance_squared(pos))
+        });
+
+        if let Some(nearest) = nearest {
+            self.nodes[idx].pos = nearest;
+        }
+    }
+
+    /// Stores every position of the node at `idx` for which the segments
+    /// either side of it each last a whole number of `divisions` per loop in
+    /// the snap guides.
+    ///
+    /// Only straight segments are snapped, as the length of a curve doesn't
+    /// follow from the distance between its ends. Segments which follow a
+    /// path are straightened once their node is dragged off it.
+    ///
+    /// With `a` and `b` as the neighbouring nodes and `r` as the length of all
+    /// other segments, the segments last `m` and `n` steps when
+    /// `|p - a| = m * L / N` and `|p - b| = n * L / N`, where the total length
+    /// `L = r * N / (N - m - n)`. Each valid position is therefore one of the
+    /// (up to) two intersections of the circles around `a` and `b`.
+    fn find_snap_positions(&mut self, idx: usize, divisions: usize) {
+        let num = self.num_nodes;
+        let prev_idx = (idx + num - 1) % num;
+        let next_idx = (idx + 1) % num;
+
+        if !self.segments[prev_idx].is_straight()
+            || !self.segments[idx].is_straight()
+        {
+            return;
+        }
+
+        let a = self.nodes[prev_idx].pos;
+        let b = self.nodes[next_idx].pos;
+
+        let remaining: f32 = (0..num)
+            .filter(|&i| i != prev_idx && i != idx)
+            .map(|i| self.segments[i].length)
+            .sum();
+
+        if remaining <= f32::EPSILON {
+            return;
+        }
+
+        let n_div = divisions as f32;
+
+        for m in 1..divisions {
+            for n in 1..(divisions - m) {
+                let total = remaining * n_div / (n_div - (m + n) as f32);
+                let r_a = m as f32 * total / n_div;
+                let r_b = n as f32 * total / n_div;
+
+                let Some((p1, p2)) = circle_intersections(a, r_a, b, r_b)
+                else {
+                    continue;
+                };
+
+                for p in [p1, p2] {
+                    if self.rect.contains(p) {
+                        self.snap_guides.push(p);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the valid positions of the node being dragged.
+    pub(super) fn draw_snap_guides(&self, draw: &Draw) {
+        for &guide in &self.snap_guides {
+            draw.ellipse()
+                .xy(guide)
+                .radius(2.5)
+                .color(Rgba::new(0.0, 0.0, 0.0, 0.3));
+        }
+    }
+}
+
+/// Returns the intersections of the circle around `c0` with radius `r0` and
+/// the circle around `c1` with radius `r1`, if there are any.
+fn circle_intersections(
+    c0: Vec2,
+    r0: f32,
+    c1: Vec2,
+    r1: f32,
+) -> Option<(Vec2, Vec2)> {
+    let dist = c0.distance(c1);
+
+    if dist <= f32::EPSILON || dist > r0 + r1 || dist < (r0 - r1).abs() {
+        return None;
+    }
+
+    let along = (r0 * r0 - r1 * r1 + dist * dist) / (2.0 * dist);
+    let height = r0.mul_add(r0, -along * along).max(0.0).sqrt();
+
+    let dir = (c1 - c0) / dist;
+    let mid = c0 + dir * along;
+    let perp = vec2(-dir.y, dir.x) * height;
+
+    Some((mid + perp, mid - perp))
+}
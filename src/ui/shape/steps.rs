@@ -0,0 +1,217 @@
+//! Module for converting between shapes and text step notation, such as
+//! `x..x..x...x.x...`.
+
+use super::*;
+
+/// The MIDI note of C4, the lowest note of a lowercase pitch letter.
+const C4: f32 = 60.0;
+/// The MIDI note of `x` steps.
+const HIT_NOTE: f32 = 69.0;
+/// The MIDI note of `X` steps.
+const ACCENT_NOTE: f32 = 81.0;
+
+/// A single step of a step string.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    /// A step without a hit (`.`, `-` or `_`).
+    Rest,
+    /// A hit (`x` or `o`).
+    Hit,
+    /// An accented hit (`X` or `O`), an octave above a regular hit.
+    Accent,
+    /// A hit with a pitch letter, such as `c`, `f#` or `A`, as a MIDI note.
+    Note(f32),
+}
+
+/// Parses a step string such as `x..x..x...x.x...` or `c..e..g.`.
+///
+/// Pitch letters may be followed by `#` to sharpen them. Lowercase letters are
+/// in the fourth octave (`a` is A4), and uppercase letters are an octave
+/// higher. Whitespace and bar lines (`|`) are ignored, but at least
+/// [`MIN_NUM_VERTICES`] steps are needed.
+pub fn parse_steps(text: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        let step = match ch {
+            '.' | '-' | '_' => Step::Rest,
+            'x' | 'o' => Step::Hit,
+            'X' | 'O' => Step::Accent,
+            'a'..='g' | 'A'..='G' => {
+                let sharp = chars.next_if_eq(&'#').is_some();
+                Step::Note(letter_to_note(ch, sharp))
+            }
+            c if c.is_whitespace() || c == '|' => continue,
+            c => return Err(format!("Unknown step \"{c}\"")),
+        };
+
+        steps.push(step);
+    }
+
+    if steps.len() < MIN_NUM_VERTICES {
+        return Err(format!("At least {MIN_NUM_VERTICES} steps are needed"));
+    }
+    if steps.len() > NODE_CAPACITY {
+        return Err(format!(
+            "Step strings are limited to {NODE_CAPACITY} steps"
+        ));
+    }
+
+    Ok(steps)
+}
+
+/// Converts a pitch letter into a MIDI note.
+fn letter_to_note(letter: char, sharp: bool) -> f32 {
+    let semitones = match letter.to_ascii_lowercase() {
+        'c' => 0.0,
+        'd' => 2.0,
+        'e' => 4.0,
+        'f' => 5.0,
+        'g' => 7.0,
+        'a' => 9.0,
+        _ => 11.0,
+    };
+    let octave = if letter.is_ascii_uppercase() { 12.0 } else { 0.0 };
+
+    C4 + semitones + octave + if sharp { 1.0 } else { 0.0 }
+}
+
+/// Converts a MIDI note into the step which represents it.
+fn note_to_step_text(note: f32) -> String {
+    if epsilon_eq(note, HIT_NOTE) {
+        return "x".to_string();
+    }
+    if epsilon_eq(note, ACCENT_NOTE) {
+        return "X".to_string();
+    }
+
+    let rounded = note.round();
+    if !epsilon_eq(note, rounded) || !(C4..C4 + 24.0).contains(&rounded) {
+        return "x".to_string();
+    }
+
+    let semitone = (rounded - C4) as usize;
+    let (letter, sharp) = match semitone % 12 {
+        0 => ('c', false),
+        1 => ('c', true),
+        2 => ('d', false),
+        3 => ('d', true),
+        4 => ('e', false),
+        5 => ('f', false),
+        6 => ('f', true),
+        7 => ('g', false),
+        8 => ('g', true),
+        9 => ('a', false),
+        10 => ('a', true),
+        _ => ('b', false),
+    };
+    let letter =
+        if semitone >= 12 { letter.to_ascii_uppercase() } else { letter };
+
+    if sharp { format!("{letter}#") } else { letter.to_string() }
+}
+
+impl Sequence {
+    /// Lays the sequence out as a regular polygon with one vertex per step,
+    /// where rests are silent and hits play the pitch of their step. Only
+    /// accented steps are accented.
+    pub fn set_steps(&mut self, steps: &[Step]) {
+        let pattern: Vec<bool> =
+            steps.iter().map(|step| *step != Step::Rest).collect();
+        self.set_step_pattern(&pattern);
+
+        for (node, step) in self.nodes.iter_mut().zip(steps) {
+            node.set_accent(*step == Step::Accent);
+
+            if let Step::Note(note) = step {
+                node.note_data_mut().note = *note;
+            }
+        }
+    }
+
+    /// Converts the shape into the nearest step string with `resolution` steps
+    /// per loop, rounding each vertex to its nearest step.
+    ///
+    /// If more than one vertex rounds to the same step, the first is used.
+    pub fn to_step_string(&self, resolution: usize) -> String {
+        let resolution = resolution.max(1);
+        let mut steps = vec![None; resolution];
+        let mut elapsed = 0.0;
+
+        for (node, segment) in self
+            .nodes
+            .iter()
+            .zip(&self.segments)
+            .take(self.num_nodes)
+        {
+            let fraction = elapsed / self.length.max(f32::EPSILON);
+            let step = (fraction * resolution as f32).round() as usize
+                % resolution;
+
+            if !node.is_rest && steps[step].is_none() {
+                steps[step] = Some(note_to_step_text(node.note_data().note));
+            }
+
+            elapsed += segment.length;
+        }
+
+        steps
+            .into_iter()
+            .map(|step| step.unwrap_or_else(|| ".".to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hits_rests_and_pitches() {
+        assert_eq!(
+            parse_steps("x.X- | c#_A"),
+            Ok(vec![
+                Step::Hit,
+                Step::Rest,
+                Step::Accent,
+                Step::Rest,
+                Step::Note(61.0),
+                Step::Rest,
+                Step::Note(81.0),
+            ])
+        );
+        assert!(parse_steps("x.y.").is_err());
+        assert!(parse_steps("x.").is_err());
+    }
+
+    #[test]
+    fn writes_pitches_which_parse_back_to_the_same_note() {
+        for note in 60..84 {
+            let note = note as f32;
+            let text = note_to_step_text(note);
+
+            let parsed = match parse_steps(&format!("{text}..")).as_deref() {
+                Ok([Step::Hit, ..]) => HIT_NOTE,
+                Ok([Step::Accent, ..]) => ACCENT_NOTE,
+                Ok([Step::Note(parsed), ..]) => *parsed,
+                other => panic!("{text} parsed as {other:?}"),
+            };
+
+            assert_eq!(parsed, note, "{text}");
+        }
+    }
+
+    #[test]
+    fn step_strings_survive_a_round_trip() {
+        let rect = Rect::from_w_h(1000.0, 1000.0);
+        let mut seq = Sequence::new(rect, mpsc::channel().0, 4);
+
+        for text in ["x..x", "x..x..x...x.X...", ".x.X", "c.e.g.C#.", "xxx"] {
+            let steps = parse_steps(text).expect("the steps are valid");
+            seq.set_steps(&steps);
+
+            assert_eq!(seq.to_step_string(steps.len()), text);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Module for subdivision ticks, which play notes part of the way along a
+//! segment.
+
+use super::*;
+
+/// The largest number of ticks on one segment which have their own offsets.
+pub const MAX_TICKS: usize = 8;
+/// The largest number of steps in the global tick grid.
+pub const MAX_TICK_GRID: u32 = 64;
+
+/// How a tick's note differs from the note of its segment's start node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TickOffset {
+    /// The tick's transposition, in semitones.
+    pub pitch: f32,
+    /// The tick's velocity, relative to the start node's (`0.0` to `1.0`).
+    pub velocity: f32,
+}
+
+impl Default for TickOffset {
+    fn default() -> Self {
+        Self { pitch: 0.0, velocity: 1.0 }
+    }
+}
+
+/// The ticks on the segment which starts at a node.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ticks {
+    /// The number of evenly spaced ticks on the segment, when the global tick
+    /// grid is off.
+    pub count: u32,
+    /// The offset of each tick, in order along the segment. Ticks past the
+    /// last share its offset.
+    pub offsets: [TickOffset; MAX_TICKS],
+}
+
+impl Ticks {
+    /// Returns the offset of the `idx`th tick along the segment.
+    pub fn offset(&self, idx: usize) -> TickOffset {
+        self.offsets[idx.min(MAX_TICKS - 1)]
+    }
+}
+
+/// Returns the index and position (as an interpolation value between the
+/// segment's start and end) of each tick on the `idx`th of `segments`.
+///
+/// If `grid` is set, ticks are placed on a grid of that many steps around
+/// the whole shape (skipping points at vertices); otherwise the segment has
+/// `ticks.count` evenly spaced ticks.
+pub(super) fn tick_points(
+    segments: &[Segment],
+    idx: usize,
+    ticks: &Ticks,
+    grid: Option<u32>,
+) -> impl Iterator<Item = (usize, f32)> {
+    // a small tolerance, in steps, so that grid points at vertices are skipped
+    const TOLERANCE: f32 = 1e-4;
+
+    let len = segments[idx].length;
+
+    let (num, first, spacing) = match grid {
+        _ if len <= 0.0 => (0, 0.0, 0.0),
+        Some(steps) => {
+            let total: f32 = segments.iter().map(|s| s.length).sum();
+            let step = total / steps.clamp(1, MAX_TICK_GRID) as f32;
+            let start: f32 = segments[..idx].iter().map(|s| s.length).sum();
+
+            let first_step = (start / step + TOLERANCE).floor() + 1.0;
+            let last_step = ((start + len) / step - TOLERANCE).ceil() - 1.0;
+            let num = (last_step - first_step + 1.0).max(0.0) as usize;
+
+            (num, first_step.mul_add(step, -start) / len, step / len)
+        }
+        None => {
+            let spacing = 1.0 / (ticks.count + 1) as f32;
+            (ticks.count as usize, spacing, spacing)
+        }
+    };
+
+    (0..num).map(move |i| (i, (i as f32).mul_add(spacing, first)))
+}
+
+impl Sequence {
+    /// Draws a small mark across each segment at each of its ticks.
+    pub(super) fn draw_ticks(&self, draw: &Draw) {
+        const HALF_LENGTH: f32 = 5.0;
+
+        let segments = &self.segments[..self.num_nodes];
+
+        for (idx, segment) in segments.iter().enumerate() {
+            let ticks = &self.nodes[idx].ticks;
+
+            for (_, interp) in tick_points(segments, idx, ticks, self.tick_grid)
+            {
+                let pos = segment.point_at(interp);
+                let normal = segment.direction_at(interp).perp();
+
+                draw.line()
+                    .start(pos - normal * HALF_LENGTH)
+                    .end(pos + normal * HALF_LENGTH)
+                    .color(BLACK)
+                    .weight(2.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Module for per-node trigger probabilities and conditions.
+
+use super::*;
+
+/// The number of fixed conditions before the `A:B` conditions.
+const NUM_FIXED_CONDITIONS: usize = 9;
+/// The longest cycle of an `A:B` condition, in loops.
+const MAX_CONDITION_LOOPS: u32 = 8;
+
+/// A condition which decides whether a node plays on a given loop, in the
+/// style of Elektron's trig conditions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrigCondition {
+    /// Plays on every loop.
+    #[default]
+    Always,
+    /// Only plays on the first loop.
+    First,
+    /// Plays on every loop but the first.
+    NotFirst,
+    /// Only plays while fill is active.
+    Fill,
+    /// Only plays while fill isn't active.
+    NotFill,
+    /// Only plays if the last conditional trigger on the shape played.
+    Previous,
+    /// Only plays if the last conditional trigger on the shape didn't play.
+    NotPrevious,
+    /// Only plays if the last conditional trigger at the vertex before played.
+    Neighbour,
+    /// Only plays if the last conditional trigger at the vertex before didn't
+    /// play.
+    NotNeighbour,
+    /// `Ratio(a, b)` plays on the `a`th loop of every `b` loops.
+    Ratio(u32, u32),
+}
+
+impl TrigCondition {
+    /// The number of conditions which can be selected from the UI.
+    pub const COUNT: usize = NUM_FIXED_CONDITIONS
+        + (MAX_CONDITION_LOOPS * (MAX_CONDITION_LOOPS + 1) / 2 - 1) as usize;
+
+    /// Every condition, in the order they are shown by the UI: the fixed
+    /// conditions first, then `1:2`, `2:2`, `1:3` and so on up to `8:8`.
+    pub const ALL: [Self; Self::COUNT] = {
+        let mut all = [Self::Always; Self::COUNT];
+        let mut idx = 0;
+
+        while idx < Self::COUNT {
+            all[idx] = Self::nth(idx);
+            idx += 1;
+        }
+
+        all
+    };
+
+    /// Returns the condition at `idx` in [`TrigCondition::ALL`], or `Always`
+    /// if `idx` is out of range.
+    pub fn from_index(idx: usize) -> Self {
+        Self::ALL.get(idx).copied().unwrap_or_default()
+    }
+
+    /// Works out the `idx`th condition of [`TrigCondition::ALL`].
+    const fn nth(idx: usize) -> Self {
+        match idx {
+            0 => Self::Always,
+            1 => Self::First,
+            2 => Self::NotFirst,
+            3 => Self::Fill,
+            4 => Self::NotFill,
+            5 => Self::Previous,
+            6 => Self::NotPrevious,
+            7 => Self::Neighbour,
+            8 => Self::NotNeighbour,
+            _ => {
+                let mut remaining = (idx - NUM_FIXED_CONDITIONS) as u32;
+                let mut b = 2;
+
+                while b <= MAX_CONDITION_LOOPS {
+                    if remaining < b {
+                        return Self::Ratio(remaining + 1, b);
+                    }
+
+                    remaining -= b;
+                    b += 1;
+                }
+
+                Self::Always
+            }
+        }
+    }
+
+    /// Returns the index of the condition, as shown by the UI. See
+    /// [`TrigCondition::from_index()`].
+    pub fn index(self) -> usize {
+        match self {
+            Self::Always => 0,
+            Self::First => 1,
+            Self::NotFirst => 2,
+            Self::Fill => 3,
+            Self::NotFill => 4,
+            Self::Previous => 5,
+            Self::NotPrevious => 6,
+            Self::Neighbour => 7,
+            Self::NotNeighbour => 8,
+            Self::Ratio(a, b) => {
+                let preceding: u32 = (2..b).sum();
+                NUM_FIXED_CONDITIONS + (preceding + a - 1) as usize
+            }
+        }
+    }
+
+    /// The condition's short display name.
+    pub fn name(self) -> String {
+        match self {
+            Self::Always => "---".to_string(),
+            Self::First => "1ST".to_string(),
+            Self::NotFirst => "!1ST".to_string(),
+            Self::Fill => "FILL".to_string(),
+            Self::NotFill => "!FILL".to_string(),
+            Self::Previous => "PRE".to_string(),
+            Self::NotPrevious => "!PRE".to_string(),
+            Self::Neighbour => "NEI".to_string(),
+            Self::NotNeighbour => "!NEI".to_string(),
+            Self::Ratio(a, b) => format!("{a}:{b}"),
+        }
+    }
+
+    /// Returns whether the condition is met in `context`.
+    pub fn is_met(self, context: TrigContext) -> bool {
+        let TrigContext { loop_idx, fill, previous, neighbour } = context;
+
+        match self {
+            Self::Always => true,
+            Self::First => loop_idx == 0,
+            Self::NotFirst => loop_idx != 0,
+            Self::Fill => fill,
+            Self::NotFill => !fill,
+            Self::Previous => previous,
+            Self::NotPrevious => !previous,
+            Self::Neighbour => neighbour,
+            Self::NotNeighbour => !neighbour,
+            Self::Ratio(a, b) => {
+                loop_idx.rem_euclid(b.max(1) as i64) == a as i64 - 1
+            }
+        }
+    }
+
+    /// Returns whether the condition depends on other triggers.
+    fn follows_others(self) -> bool {
+        matches!(
+            self,
+            Self::Previous
+                | Self::NotPrevious
+                | Self::Neighbour
+                | Self::NotNeighbour
+        )
+    }
+}
+
+/// What a trigger condition is tested against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrigContext {
+    /// The loop being played, counting from `0`.
+    pub loop_idx: i64,
+    /// Whether fill is active.
+    pub fill: bool,
+    /// Whether the last conditional trigger on the shape played.
+    pub previous: bool,
+    /// Whether the last conditional trigger at the vertex before played.
+    pub neighbour: bool,
+}
+
+/// Decides whether a node plays each time a playhead arrives at it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trigger {
+    /// The chance of the node playing when its condition is met (`0.0` to
+    /// `1.0`).
+    pub probability: f32,
+    pub condition: TrigCondition,
+}
+
+impl Default for Trigger {
+    fn default() -> Self {
+        Self { probability: 1.0, condition: TrigCondition::Always }
+    }
+}
+
+impl Trigger {
+    /// Returns whether the node should play in `context`. `rng` is only used
+    /// if the probability is below `1.0`.
+    pub fn should_play(&self, context: TrigContext, rng: &mut Rng) -> bool {
+        self.condition.is_met(context)
+            && (self.probability >= 1.0 || rng.chance(self.probability))
+    }
+
+    /// Returns whether the trigger is conditional, so `PRE` and `NEI`
+    /// conditions follow whether it played. Triggers which follow others
+    /// aren't, so that they never follow themselves.
+    fn is_conditional(&self) -> bool {
+        !self.condition.follows_others()
+            && (self.condition != TrigCondition::Always
+                || self.probability < 1.0)
+    }
+}
+
+/// Whether the conditional triggers of a shape played, which `PRE` and `NEI`
+/// conditions follow.
+#[derive(Clone, Debug)]
+pub(super) struct TrigHistory {
+    /// Whether the last conditional trigger on the shape played.
+    previous: bool,
+    /// Whether the last conditional trigger at each vertex played.
+    vertices: Vec<bool>,
+}
+
+impl Default for TrigHistory {
+    fn default() -> Self {
+        Self { previous: false, vertices: vec![false; NODE_CAPACITY] }
+    }
+}
+
+impl TrigHistory {
+    /// Returns the context of a trigger at `vertex` of a shape with `num`
+    /// vertices, on loop `loop_idx` and given whether fill is active.
+    pub fn context(
+        &self,
+        vertex: usize,
+        num: usize,
+        loop_idx: i64,
+        fill: bool,
+    ) -> TrigContext {
+        TrigContext {
+            loop_idx,
+            fill,
+            previous: self.previous,
+            neighbour: self.vertices[(vertex + num - 1) % num],
+        }
+    }
+
+    /// Records whether `trigger`, at `vertex`, played, if it is conditional.
+    pub fn record(&mut self, vertex: usize, trigger: &Trigger, played: bool) {
+        if trigger.is_conditional() {
+            self.previous = played;
+            self.vertices[vertex] = played;
+        }
+    }
+
+    /// Forgets every trigger which has played.
+    pub fn reset(&mut self) {
+        self.previous = false;
+        self.vertices.fill(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the context of loop `loop_idx` without fill, where no other
+    /// trigger has played.
+    fn on_loop(loop_idx: i64) -> TrigContext {
+        TrigContext { loop_idx, ..TrigContext::default() }
+    }
+
+    #[test]
+    fn indices_round_trip() {
+        for (idx, condition) in TrigCondition::ALL.into_iter().enumerate() {
+            assert_eq!(condition.index(), idx, "{}", condition.name());
+            assert_eq!(TrigCondition::from_index(idx), condition);
+        }
+
+        assert_eq!(TrigCondition::ALL[9], TrigCondition::Ratio(1, 2));
+        assert_eq!(TrigCondition::ALL[43], TrigCondition::Ratio(8, 8));
+    }
+
+    #[test]
+    fn ratios_play_on_one_loop_of_each_cycle() {
+        let plays = |condition: TrigCondition| -> Vec<i64> {
+            (0..8).filter(|&i| condition.is_met(on_loop(i))).collect()
+        };
+
+        assert_eq!(plays(TrigCondition::Ratio(1, 2)), [0, 2, 4, 6]);
+        assert_eq!(plays(TrigCondition::Ratio(2, 4)), [1, 5]);
+    }
+
+    #[test]
+    fn fill_and_other_triggers_decide_their_conditions() {
+        let fill = TrigContext { fill: true, ..on_loop(3) };
+        assert!(TrigCondition::Fill.is_met(fill));
+        assert!(!TrigCondition::Fill.is_met(on_loop(3)));
+        assert!(TrigCondition::NotFill.is_met(on_loop(3)));
+
+        let previous = TrigContext { previous: true, ..on_loop(0) };
+        assert!(TrigCondition::Previous.is_met(previous));
+        assert!(!TrigCondition::Previous.is_met(on_loop(0)));
+        assert!(!TrigCondition::NotPrevious.is_met(previous));
+
+        let neighbour = TrigContext { neighbour: true, ..on_loop(0) };
+        assert!(TrigCondition::Neighbour.is_met(neighbour));
+        assert!(!TrigCondition::Neighbour.is_met(previous));
+        assert!(TrigCondition::NotNeighbour.is_met(previous));
+    }
+
+    #[test]
+    fn history_follows_only_conditional_triggers() {
+        let mut history = TrigHistory::default();
+        let trigger = |condition| Trigger { probability: 1.0, condition };
+
+        history.record(1, &trigger(TrigCondition::Ratio(1, 2)), true);
+        history.record(2, &trigger(TrigCondition::Always), false);
+        history.record(3, &trigger(TrigCondition::Previous), false);
+
+        let context = history.context(2, 4, 0, false);
+        assert!(context.previous && context.neighbour);
+        assert!(!history.context(0, 4, 0, false).neighbour);
+
+        history.reset();
+        assert!(!history.context(2, 4, 0, false).previous);
+    }
+}
@@ -0,0 +1,108 @@
+//! Single-line text input UI component.
+
+use super::*;
+
+/// The maximum number of chars which can be typed into a `TextInput`.
+const MAX_CHARS: usize = 64;
+
+/// A single line of editable text with a prompt, such as `"Durations: "`.
+///
+/// The `TextInput` doesn't listen to the keyboard itself: characters should be
+/// forwarded to [`push()`](Self::push) and backspace to [`pop()`](Self::pop)
+/// while it is active.
+pub struct TextInput {
+    prompt: String,
+    text: String,
+    rect: Rect,
+
+    is_active: bool,
+    /// Whether to ignore the next char, which is usually the char of the key
+    /// which opened the input.
+    swallow_next: bool,
+}
+
+impl TextInput {
+    /// Creates a new, inactive `TextInput`.
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            prompt: String::new(),
+            text: String::with_capacity(MAX_CHARS),
+            rect,
+
+            is_active: false,
+            swallow_next: false,
+        }
+    }
+
+    /// Activates the `TextInput` with an empty line and the provided prompt.
+    ///
+    /// The next char received in the same frame is ignored, as it is usually
+    /// the key which opened the input.
+    pub fn open(&mut self, prompt: &str) {
+        self.prompt.clear();
+        self.prompt.push_str(prompt);
+        self.text.clear();
+
+        self.is_active = true;
+        self.swallow_next = true;
+    }
+
+    /// Deactivates the `TextInput`, returning the text it held.
+    pub fn close(&mut self) -> String {
+        self.is_active = false;
+        std::mem::take(&mut self.text)
+    }
+
+    /// Returns whether the `TextInput` is currently active.
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Appends `ch` to the text, ignoring control characters.
+    pub fn push(&mut self, ch: char) {
+        if std::mem::take(&mut self.swallow_next) {
+            return;
+        }
+
+        if self.is_active && !ch.is_control() && self.text.len() < MAX_CHARS {
+            self.text.push(ch);
+        }
+    }
+
+    /// Appends each char of `text`, such as text pasted from the clipboard.
+    pub fn push_str(&mut self, text: &str) {
+        self.swallow_next = false;
+        text.chars().for_each(|ch| self.push(ch));
+    }
+
+    /// Removes the last char of the text.
+    pub fn pop(&mut self) {
+        self.text.pop();
+    }
+}
+
+impl Drawable for TextInput {
+    fn update(&mut self, _: &InputData) {
+        self.swallow_next = false;
+    }
+
+    fn force_redraw(&self, draw: &Draw, _: &Frame) {
+        draw.rect()
+            .xy(self.rect.xy())
+            .wh(self.rect.wh())
+            .color(BG_NON_SELECTED);
+
+        let text_rect = self.rect.pad_left(8.0);
+
+        draw.text(&format!("{}{}_", self.prompt, self.text))
+            .xy(text_rect.xy())
+            .wh(text_rect.wh())
+            .color(VALUE)
+            .font_size(16)
+            .left_justify();
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+}
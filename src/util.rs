@@ -2,9 +2,11 @@
 
 pub mod atomic_ops;
 pub mod ramp;
+pub mod rng;
 
 pub use atomic_ops::AtomicOps;
 pub use ramp::Ramp;
+pub use rng::Rng;
 
 #[derive(Debug, Copy, Clone, Default)]
 pub enum SmoothingType {
@@ -98,6 +100,24 @@ pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
     t.mul_add(b - a, a)
 }
 
+/// Returns the text held by the system clipboard, if there is any.
+pub fn clipboard_text(
+    clipboard: Option<&mut arboard::Clipboard>,
+) -> Option<String> {
+    clipboard?.get_text().ok()
+}
+
+/// Copies `text` to the system clipboard, returning whether it succeeded.
+///
+/// On some platforms (such as X11) the text is only available while
+/// `clipboard` is alive, so it should be kept for as long as the app runs.
+pub fn set_clipboard_text(
+    clipboard: Option<&mut arboard::Clipboard>,
+    text: &str,
+) -> bool {
+    clipboard.is_some_and(|clipboard| clipboard.set_text(text).is_ok())
+}
+
 /// "Inverse linear interpolation": finds the interpolation value
 /// within a range.
 pub fn ilerp(a: f32, b: f32, val: f32) -> f32 {
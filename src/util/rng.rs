@@ -0,0 +1,66 @@
+//! Seedable pseudo-random number generator.
+
+use crate::prelude::*;
+
+/// A small, fast pseudo-random number generator (xorshift64*).
+///
+/// The same seed always produces the same sequence of values, so anything
+/// driven by an `Rng` can be reproduced exactly.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new `Rng` from `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.reseed(seed);
+        rng
+    }
+
+    /// Restarts the sequence of values from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        // splitmix64 spreads nearby seeds apart, and never produces the zero
+        // state which xorshift can't escape from
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        self.state = (z ^ (z >> 31)).max(1);
+    }
+
+    /// Returns the next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a random value between `0.0` (inclusive) and `1.0` (exclusive).
+    pub fn next_f32(&mut self) -> f32 {
+        // the top 24 bits fill an f32's mantissa exactly
+        (self.next_u64() >> 40) as f32 / (1_u32 << 24) as f32
+    }
+
+    /// Returns a random value between `min` (inclusive) and `max` (exclusive).
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        scale(self.next_f32(), min, max)
+    }
+
+    /// Returns `true` with a probability of `chance` (`0.0` to `1.0`).
+    pub fn chance(&mut self, chance: f32) -> bool {
+        self.next_f32() < chance
+    }
+
+    /// Returns a normally-distributed random value with a mean of `0.0` and a
+    /// standard deviation of `1.0` (via the Box-Muller transform).
+    pub fn gaussian(&mut self) -> f32 {
+        let u1 = 1.0 - self.next_f32();
+        let u2 = self.next_f32();
+
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}